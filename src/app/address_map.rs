@@ -0,0 +1,316 @@
+use std::{
+  borrow::Borrow,
+  collections::HashMap,
+  hash::Hash,
+  str::FromStr,
+  sync::RwLock,
+};
+use bech32::{FromBase32, ToBase32, Variant};
+use tokio::sync::{Mutex, MutexGuard};
+use namada_sdk::{proof_of_stake::Epoch, types::address::Address};
+
+const VALOPER_SUFFIX: &str = "valoper";
+const VALCONS_SUFFIX: &str = "valcons";
+/// Most translations kept in each direction; addresses come from request paths, so the cache has to be bounded
+const MAX_CACHED_TRANSLATIONS: usize = 10_000;
+
+/// Translates between native Namada addresses and the address forms Ping.pub expects from a Cosmos chain:
+/// - `tnamvaloper1...`: a validator's operator address; same payload as the `tnam1...` address with a different prefix
+/// - `tnamvalcons1...` (or the plain hex form): a validator's CometBFT consensus address
+/// Synthetic addresses use the original bech32 checksum (not bech32m), since that's what the explorer can decode
+pub struct AddressMap {
+  // when set, validator addresses in responses are given in their synthetic form
+  compat_addresses: bool,
+  // uppercase hex consensus address <-> validator address
+  // consensus addresses from previous keys are kept, so that older blocks can still be attributed to their validator
+  consensus_to_validator: RwLock<HashMap<String, Address>>,
+  validator_to_consensus: RwLock<HashMap<Address, String>>,
+  // epoch at which the consensus addresses were last queried
+  consensus_epoch: RwLock<Option<Epoch>>,
+  // held while the consensus addresses are requeried, so that concurrent requests don't each requery them
+  refresh_lock: Mutex<()>,
+  // synthetic/alternative address strings that have already been translated, both ways
+  to_native: TranslationCache<String, Address>,
+  to_valoper: TranslationCache<Address, String>,
+  // `valcons` address <-> hex consensus address
+  to_consensus: TranslationCache<String, String>,
+  to_valcons: TranslationCache<String, String>,
+}
+
+/// A translation cache that's emptied once it's full; the translations are cheap to redo, so this only saves
+/// re-encoding the same addresses on every request without letting arbitrary request addresses pile up
+struct TranslationCache<K, V> {
+  entries: RwLock<HashMap<K, V>>,
+}
+
+impl<K: Hash + Eq, V: Clone> TranslationCache<K, V> {
+  fn new() -> Self {
+    TranslationCache { entries: RwLock::new(HashMap::new()) }
+  }
+
+  fn get<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<V> where K: Borrow<Q> {
+    self.entries.read().unwrap().get(key).cloned()
+  }
+
+  fn insert(&self, key: K, value: V) {
+    let mut entries = self.entries.write().unwrap();
+    if entries.len() >= MAX_CACHED_TRANSLATIONS {
+      entries.clear();
+    }
+    entries.insert(key, value);
+  }
+}
+
+impl AddressMap {
+  pub fn new(compat_addresses: bool) -> Self {
+    AddressMap {
+      compat_addresses,
+      consensus_to_validator: RwLock::new(HashMap::new()),
+      validator_to_consensus: RwLock::new(HashMap::new()),
+      consensus_epoch: RwLock::new(None),
+      refresh_lock: Mutex::new(()),
+      to_native: TranslationCache::new(),
+      to_valoper: TranslationCache::new(),
+      to_consensus: TranslationCache::new(),
+      to_valcons: TranslationCache::new(),
+    }
+  }
+
+  /// Translates any address form that doesn't need the consensus address lookup
+  /// Returns None if the string is a consensus address (or not an address at all)
+  pub fn resolve_account(&self, address: &str) -> Option<Address> {
+    if let Ok(address) = Address::from_str(address) {
+      return Some(address);
+    }
+    if let Some(address) = self.to_native.get(address) {
+      return Some(address);
+    }
+
+    let (hrp, data, _) = bech32::decode(address).ok()?;
+    // valoper addresses, and native addresses that an explorer re-encoded with the wrong checksum variant
+    let native_hrp = hrp.strip_suffix(VALOPER_SUFFIX).unwrap_or(&hrp);
+    if native_hrp.ends_with(VALCONS_SUFFIX) {
+      return None;
+    }
+    let native = bech32::encode(native_hrp, data, Variant::Bech32m).ok()?;
+    let resolved = Address::from_str(&native).ok()?;
+
+    self.to_native.insert(address.to_string(), resolved.clone());
+    if hrp.ends_with(VALOPER_SUFFIX) {
+      self.to_valoper.insert(resolved.clone(), address.to_string());
+    }
+    Some(resolved)
+  }
+
+  /// Extracts the hex consensus address from a `valcons` or hex string
+  pub fn parse_consensus_address(&self, address: &str) -> Option<String> {
+    if address.len() == 40 && hex::decode(address).is_ok() {
+      return Some(address.to_uppercase());
+    }
+
+    if let Some(consensus_address) = self.to_consensus.get(address) {
+      return Some(consensus_address);
+    }
+
+    let (hrp, data, _) = bech32::decode(address).ok()?;
+    if !hrp.ends_with(VALCONS_SUFFIX) {
+      return None;
+    }
+    let bytes = Vec::<u8>::from_base32(&data).ok()?;
+    let consensus_address = hex::encode_upper(bytes);
+
+    self.to_consensus.insert(address.to_string(), consensus_address.clone());
+    self.to_valcons.insert(consensus_address.clone(), address.to_string());
+    Some(consensus_address)
+  }
+
+  /// Converts a validator's address to its synthetic `valoper` form
+  pub fn to_valoper(&self, address: &Address) -> String {
+    if let Some(valoper) = self.to_valoper.get(address) {
+      return valoper;
+    }
+
+    let native = address.to_string();
+    let valoper = match bech32::decode(&native) {
+      Ok((hrp, data, _)) => bech32::encode(&format!("{}{}", hrp, VALOPER_SUFFIX), data, Variant::Bech32),
+      Err(_) => return native,
+    };
+    match valoper {
+      Ok(valoper) => {
+        self.to_valoper.insert(address.clone(), valoper.clone());
+        self.to_native.insert(valoper.clone(), address.clone());
+        valoper
+      },
+      Err(_) => native,
+    }
+  }
+
+  /// Converts a hex consensus address to its synthetic `valcons` form
+  pub fn to_valcons(&self, validator: &Address, consensus_address: &str) -> String {
+    let consensus_address = consensus_address.to_uppercase();
+    if let Some(valcons) = self.to_valcons.get(&consensus_address) {
+      return valcons;
+    }
+
+    let native = validator.to_string();
+    let hrp = match bech32::decode(&native) {
+      Ok((hrp, _, _)) => hrp,
+      Err(_) => return consensus_address,
+    };
+    let valcons = match hex::decode(&consensus_address) {
+      Ok(bytes) => bech32::encode(&format!("{}{}", hrp, VALCONS_SUFFIX), bytes.to_base32(), Variant::Bech32),
+      Err(_) => return consensus_address,
+    };
+    match valcons {
+      Ok(valcons) => {
+        self.to_valcons.insert(consensus_address.clone(), valcons.clone());
+        self.to_consensus.insert(valcons.clone(), consensus_address);
+        valcons
+      },
+      Err(_) => consensus_address,
+    }
+  }
+
+  /// The operator address to return in a response
+  pub fn operator_address(&self, validator: &Address) -> String {
+    if self.compat_addresses {
+      self.to_valoper(validator)
+    } else {
+      validator.to_string()
+    }
+  }
+
+  /// The address used to identify a validator in consensus related responses (eg: validator sets)
+  pub fn consensus_display_address(&self, validator: &Address) -> String {
+    match (self.compat_addresses, self.consensus_address(validator)) {
+      (true, Some(consensus_address)) => self.to_valcons(validator, &consensus_address),
+      _ => validator.to_string(),
+    }
+  }
+
+  pub fn validator_by_consensus(&self, consensus_address: &str) -> Option<Address> {
+    self.consensus_to_validator.read().unwrap().get(&consensus_address.to_uppercase()).cloned()
+  }
+
+  /// Whether the consensus addresses need to be (re)queried, ie: they haven't been queried yet during `current_epoch`
+  pub fn consensus_addresses_stale(&self, current_epoch: Epoch) -> bool {
+    *self.consensus_epoch.read().unwrap() != Some(current_epoch)
+  }
+
+  /// Waits for any refresh of the consensus addresses in progress; staleness should be checked again once held
+  pub async fn lock_refresh(&self) -> MutexGuard<'_, ()> {
+    self.refresh_lock.lock().await
+  }
+
+  pub fn consensus_address(&self, validator: &Address) -> Option<String> {
+    self.validator_to_consensus.read().unwrap().get(validator).cloned()
+  }
+
+  /// Updates the consensus address mapping with freshly queried (validator, hex consensus address) pairs
+  pub fn update_consensus_addresses(&self, epoch: Epoch, entries: Vec<(Address, String)>) {
+    let mut consensus_to_validator = self.consensus_to_validator.write().unwrap();
+    let mut validator_to_consensus = self.validator_to_consensus.write().unwrap();
+    for (validator, consensus_address) in entries {
+      let consensus_address = consensus_address.to_uppercase();
+      consensus_to_validator.insert(consensus_address.clone(), validator.clone());
+      validator_to_consensus.insert(validator, consensus_address);
+    }
+    *self.consensus_epoch.write().unwrap() = Some(epoch);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use namada_sdk::types::{
+    address::{EstablishedAddressGen, ImplicitAddress, InternalAddress},
+    key::PublicKeyHash,
+  };
+
+  const CONSENSUS_ADDRESS: &str = "0A1B2C3D4E5F60718293A4B5C6D7E8F901234567";
+
+  fn validator() -> Address {
+    Address::Internal(InternalAddress::PoS)
+  }
+
+  #[test]
+  fn valoper_round_trip() {
+    let address_map = AddressMap::new(true);
+    let valoper = address_map.to_valoper(&validator());
+    assert!(valoper.starts_with(&format!("tnam{}1", VALOPER_SUFFIX)));
+    assert_eq!(address_map.resolve_account(&valoper), Some(validator()));
+    assert_eq!(address_map.operator_address(&validator()), valoper);
+  }
+
+  #[test]
+  fn established_and_implicit_validator_round_trip() {
+    let established = EstablishedAddressGen::new("validator seed").generate_address("validator");
+    let implicit = Address::Implicit(ImplicitAddress(PublicKeyHash([7; 20])));
+    for validator in [established, implicit] {
+      // fresh maps, so the translation is decoded rather than read back from the cache
+      let valoper = AddressMap::new(true).to_valoper(&validator);
+      assert!(valoper.starts_with(&format!("tnam{}1", VALOPER_SUFFIX)));
+      assert_eq!(AddressMap::new(true).resolve_account(&valoper), Some(validator.clone()));
+
+      let valcons = AddressMap::new(true).to_valcons(&validator, CONSENSUS_ADDRESS);
+      assert_eq!(AddressMap::new(true).parse_consensus_address(&valcons), Some(CONSENSUS_ADDRESS.to_string()));
+    }
+  }
+
+  #[test]
+  fn translations_are_cached_both_ways() {
+    let address_map = AddressMap::new(true);
+    let valoper = address_map.to_valoper(&validator());
+    assert_eq!(address_map.to_native.get(valoper.as_str()), Some(validator()));
+
+    let valcons = address_map.to_valcons(&validator(), &CONSENSUS_ADDRESS.to_lowercase());
+    assert_eq!(address_map.to_consensus.get(valcons.as_str()), Some(CONSENSUS_ADDRESS.to_string()));
+    assert_eq!(address_map.to_valcons.get(CONSENSUS_ADDRESS), Some(valcons));
+  }
+
+  #[test]
+  fn translation_cache_is_bounded() {
+    let cache = TranslationCache::new();
+    for key in 0..MAX_CACHED_TRANSLATIONS + 1 {
+      cache.insert(key, key);
+    }
+    assert_eq!(cache.entries.read().unwrap().len(), 1);
+    assert_eq!(cache.get(&MAX_CACHED_TRANSLATIONS), Some(MAX_CACHED_TRANSLATIONS));
+  }
+
+  #[test]
+  fn native_address_resolves_to_itself() {
+    let address_map = AddressMap::new(false);
+    assert_eq!(address_map.resolve_account(&validator().to_string()), Some(validator()));
+    assert_eq!(address_map.operator_address(&validator()), validator().to_string());
+  }
+
+  #[test]
+  fn valcons_round_trip() {
+    let address_map = AddressMap::new(true);
+    let valcons = address_map.to_valcons(&validator(), CONSENSUS_ADDRESS);
+    assert!(valcons.starts_with(&format!("tnam{}1", VALCONS_SUFFIX)));
+    assert_eq!(address_map.parse_consensus_address(&valcons), Some(CONSENSUS_ADDRESS.to_string()));
+    assert_eq!(address_map.resolve_account(&valcons), None);
+  }
+
+  #[test]
+  fn hex_consensus_address_is_uppercased() {
+    let address_map = AddressMap::new(true);
+    assert_eq!(address_map.parse_consensus_address(&CONSENSUS_ADDRESS.to_lowercase()), Some(CONSENSUS_ADDRESS.to_string()));
+    assert_eq!(address_map.parse_consensus_address("not an address"), None);
+  }
+
+  #[test]
+  fn consensus_mapping_is_case_insensitive() {
+    let address_map = AddressMap::new(true);
+    assert!(address_map.consensus_addresses_stale(Epoch(3)));
+    address_map.update_consensus_addresses(Epoch(3), vec![(validator(), CONSENSUS_ADDRESS.to_lowercase())]);
+
+    assert!(!address_map.consensus_addresses_stale(Epoch(3)));
+    assert!(address_map.consensus_addresses_stale(Epoch(4)));
+    assert_eq!(address_map.validator_by_consensus(&CONSENSUS_ADDRESS.to_lowercase()), Some(validator()));
+    assert_eq!(address_map.consensus_address(&validator()), Some(CONSENSUS_ADDRESS.to_string()));
+    assert_eq!(address_map.consensus_display_address(&validator()), address_map.to_valcons(&validator(), CONSENSUS_ADDRESS));
+  }
+}
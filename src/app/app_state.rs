@@ -1,26 +1,86 @@
+use std::{
+  collections::HashMap,
+  sync::{Arc, RwLock},
+  time::Duration,
+};
 use namada_sdk::{
   Namada, NamadaImpl, wallet::fs::FsWalletUtils, masp::fs::FsShieldedUtils, io::NullIo,
 };
-use tendermint_rpc::{HttpClient, Url};
+use crate::app::{address_map::AddressMap, chain_follower::ChainSnapshot, config::{AppConfig, ConfigError, HealthConfig}, metrics::Metrics, proposal_cache::ProposalCache, response_cache::ResponseCache, rpc_pool::RpcPool, signing_history::SigningHistory};
+
+#[cfg(feature = "indexer")]
+use crate::indexer::store::IndexStore;
+
+pub type NamadaContext = NamadaImpl<RpcPool, FsWalletUtils, FsShieldedUtils, NullIo>;
 
 pub struct AppState {
   // http_client: HttpClient,
-  namada_impl: NamadaImpl<HttpClient, FsWalletUtils, FsShieldedUtils, NullIo>,
+  namada_impl: NamadaContext,
+  pub proposal_cache: ProposalCache,
+  pub response_cache: ResponseCache,
+  // validators and proposals precomputed by the chain follower once per epoch
+  snapshot: RwLock<Option<Arc<ChainSnapshot>>>,
+  pub address_map: AddressMap,
+  // epoch -> first block height of the epoch; these never change once the epoch has started
+  pub epoch_start_heights: RwLock<HashMap<u64, u64>>,
+  // signatures of the most recent blocks, for validator uptime
+  pub signing_history: SigningHistory,
+  pub metrics: Metrics,
+  pub health: HealthConfig,
+  // None when the indexer is disabled
+  #[cfg(feature = "indexer")]
+  pub index: Option<Arc<IndexStore>>,
 }
 
 impl AppState {
-  pub async fn new(rpc_url: Url) -> Self {
+  pub async fn new(config: &AppConfig) -> Result<Self, ConfigError> {
       // setup namada_impl
-      let http_client: HttpClient = HttpClient::new(rpc_url).unwrap();
-      let wallet = FsWalletUtils::new("wallet".into());
-      let shielded_ctx = FsShieldedUtils::new("masp".into());
+      let rpc_pool = RpcPool::new(&config.rpc, config.rpc_urls())?;
+      rpc_pool.check_health().await;
+      rpc_pool.spawn_health_checks(Duration::from_secs(config.rpc.health_check_interval_secs));
+      let wallet = FsWalletUtils::new(config.namada.wallet_dir.clone());
+      let shielded_ctx = FsShieldedUtils::new(config.namada.masp_dir.clone());
       let null_io = NullIo;
-      Self {
-          namada_impl: NamadaImpl::new(http_client, wallet, shielded_ctx, null_io).await.unwrap(),
-      }
+      let namada_impl = NamadaImpl::new(rpc_pool, wallet, shielded_ctx, null_io).await
+          .map_err(|err| ConfigError::Startup(format!("could not set up the namada sdk context: {}", err)))?;
+
+      #[cfg(feature = "indexer")]
+      let index = match config.indexer.enabled {
+          true => Some(Arc::new(IndexStore::open(&config.indexer.path)
+              .map_err(|err| ConfigError::Startup(format!("could not open index {}: {}", config.indexer.path.display(), err)))?)),
+          false => None,
+      };
+
+      Ok(Self {
+          namada_impl,
+          proposal_cache: ProposalCache::new(&config.cache),
+          response_cache: ResponseCache::new(&config.cache),
+          snapshot: RwLock::new(None),
+          address_map: AddressMap::new(config.namada.compat_addresses),
+          epoch_start_heights: RwLock::new(HashMap::new()),
+          signing_history: SigningHistory::new(),
+          metrics: Metrics::new(),
+          health: config.health.clone(),
+          #[cfg(feature = "indexer")]
+          index,
+      })
   }
 
-  pub fn get_client(&self) -> &HttpClient {
+  pub fn get_client(&self) -> &RpcPool {
       &self.namada_impl.client()
   }
+
+  /// The latest snapshot built by the chain follower, if one has been built yet
+  pub fn current_snapshot(&self) -> Option<Arc<ChainSnapshot>> {
+      self.snapshot.read().unwrap().clone()
+  }
+
+  pub fn set_snapshot(&self, snapshot: ChainSnapshot) {
+      *self.snapshot.write().unwrap() = Some(Arc::new(snapshot));
+  }
+
+  /// Some sdk queries need the full Namada context (eg: to look up token aliases in the wallet) rather than just the client
+  pub fn get_namada(&self) -> &NamadaContext {
+      &self.namada_impl
+  }
 }
@@ -0,0 +1,238 @@
+use std::{
+  str::FromStr,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  time::{Duration, Instant},
+};
+use futures::StreamExt;
+use serde::Serialize;
+use namada_sdk::{proof_of_stake::Epoch, rpc, types::token::Amount};
+use tendermint::Time;
+use tendermint_rpc::{query::EventType, SubscriptionClient, Url, WebSocketClient};
+use tracing::{debug, info, warn};
+
+use crate::app::{app_state::AppState, config::AppConfig, response_cache::ChainPosition};
+use crate::error::api_error::ApiError;
+use crate::handlers::{gov::get_all_proposal_data, staking::{get_validators, set_commission_update_times}, uptime::sync_signing_history};
+use crate::model::{gov::ProposalData, staking::ValidatorInfo};
+
+/// How long to poll before trying to reconnect a websocket that failed
+const WEBSOCKET_RETRY: Duration = Duration::from_secs(60);
+/// If no block arrives over the websocket for this long, the subscription is assumed to be dead
+const WEBSOCKET_STALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Explorer data that only changes once per epoch, precomputed so that handlers don't need to query the node
+pub struct ChainSnapshot {
+  pub height: u64,
+  pub epoch: Epoch,
+  pub updated_at: Time,
+  // all validators, regardless of state
+  pub validators: Vec<ValidatorInfo>,
+  pub total_staked: Amount,
+  pub proposals: Vec<ProposalData>,
+}
+
+/// When (and at which height/epoch) the snapshot a response was served from was built
+#[derive(Clone)]
+pub struct SnapshotFreshness {
+  pub height: u64,
+  pub epoch: Epoch,
+  pub updated_at: String,
+}
+
+#[derive(Serialize)]
+pub struct SnapshotStatus {
+  pub available: bool,
+  pub height: Option<String>,
+  pub epoch: Option<String>,
+  pub updated_at: Option<String>,
+  pub age_seconds: Option<i64>,
+  pub validators: usize,
+  pub proposals: usize,
+}
+
+impl SnapshotStatus {
+  pub fn new(snapshot: Option<&ChainSnapshot>) -> Self {
+    SnapshotStatus {
+      available: snapshot.is_some(),
+      height: snapshot.map(|snapshot| snapshot.height.to_string()),
+      epoch: snapshot.map(|snapshot| snapshot.epoch.to_string()),
+      updated_at: snapshot.map(|snapshot| snapshot.updated_at.to_rfc3339()),
+      age_seconds: snapshot.and_then(|snapshot| Time::now().duration_since(snapshot.updated_at).ok()).map(|age| age.as_secs() as i64),
+      validators: snapshot.map(|snapshot| snapshot.validators.len()).unwrap_or(0),
+      proposals: snapshot.map(|snapshot| snapshot.proposals.len()).unwrap_or(0),
+    }
+  }
+}
+
+impl ChainSnapshot {
+  pub fn freshness(&self) -> SnapshotFreshness {
+    SnapshotFreshness {
+      height: self.height,
+      epoch: self.epoch,
+      updated_at: self.updated_at.to_rfc3339(),
+    }
+  }
+}
+
+/// Follows new blocks, reporting each new block/epoch to the response cache and rebuilding the snapshot on each new epoch
+pub struct ChainFollower {
+  app_state: Arc<AppState>,
+  websocket_url: Option<Url>,
+  poll_interval: Duration,
+  rebuilding: Arc<AtomicBool>,
+}
+
+impl ChainFollower {
+  pub fn spawn(app_state: Arc<AppState>, config: &AppConfig) {
+    let websocket_url = match config.follower.websocket {
+      true => config.rpc_urls().first().and_then(websocket_url),
+      false => None,
+    };
+
+    let follower = ChainFollower {
+      app_state,
+      websocket_url,
+      poll_interval: Duration::from_millis(config.follower.poll_interval_ms),
+      rebuilding: Arc::new(AtomicBool::new(false)),
+    };
+    tokio::spawn(follower.run());
+  }
+
+  async fn run(self) {
+    loop {
+      if let Some(url) = &self.websocket_url {
+        match self.follow_websocket(url.clone()).await {
+          Ok(()) => warn!("block subscription on {} ended, polling for new blocks instead", url),
+          Err(err) => warn!("could not subscribe to blocks on {}, polling for new blocks instead: {}", url, err),
+        }
+      }
+
+      // with a websocket configured, polling is only a stopgap until it reconnects
+      self.poll(self.websocket_url.as_ref().map(|_| WEBSOCKET_RETRY)).await;
+    }
+  }
+
+  async fn follow_websocket(&self, url: Url) -> Result<(), tendermint_rpc::Error> {
+    let (client, driver) = WebSocketClient::new(url.clone()).await?;
+    let driver_handle = tokio::spawn(driver.run());
+    let mut subscription = client.subscribe(EventType::NewBlock.into()).await?;
+    info!("following new blocks on {}", url);
+
+    // catch up on anything missed while disconnected
+    self.on_new_block().await;
+    loop {
+      match tokio::time::timeout(WEBSOCKET_STALL_TIMEOUT, subscription.next()).await {
+        // the event itself isn't needed, the height and epoch are queried (through the rpc pool) either way
+        Ok(Some(Ok(_))) => self.on_new_block().await,
+        Ok(Some(Err(err))) => {
+          warn!("block subscription error: {}", err);
+          break;
+        },
+        Ok(None) => break,
+        Err(_) => {
+          warn!("no new block received on {} for {}s", url, WEBSOCKET_STALL_TIMEOUT.as_secs());
+          break;
+        },
+      }
+    }
+
+    client.close()?;
+    let _ = driver_handle.await;
+    Ok(())
+  }
+
+  /// Polls for new blocks, forever or for `duration`
+  async fn poll(&self, duration: Option<Duration>) {
+    let started = Instant::now();
+    let mut ticker = tokio::time::interval(self.poll_interval);
+    loop {
+      ticker.tick().await;
+      self.on_new_block().await;
+      if duration.is_some_and(|duration| started.elapsed() >= duration) {
+        return;
+      }
+    }
+  }
+
+  async fn on_new_block(&self) {
+    let client = self.app_state.get_client();
+    let position = match (rpc::query_block(client).await, rpc::query_epoch(client).await) {
+      (Ok(Some(block)), Ok(epoch)) => Some(ChainPosition { height: block.height.0, epoch: epoch.0 }),
+      (Ok(None), _) => None,
+      (Err(err), _) | (_, Err(err)) => {
+        // without a known position cached values can't be trusted to be current
+        warn!("could not query the latest block and epoch: {}", err);
+        None
+      },
+    };
+    self.app_state.response_cache.observe(position);
+
+    let Some(position) = position else {
+      return;
+    };
+
+    // keep the uptime signatures current, so that uptime requests don't have to wait for new blocks to be fetched
+    if !self.app_state.signing_history.is_syncing() {
+      let app_state = Arc::clone(&self.app_state);
+      tokio::spawn(async move {
+        if let Err(err) = sync_signing_history(&app_state).await {
+          debug!("could not record the signatures of new blocks: {}", err.error);
+        }
+      });
+    }
+
+    let snapshot_epoch = self.app_state.current_snapshot().map(|snapshot| snapshot.epoch.0);
+    if snapshot_epoch == Some(position.epoch) || self.rebuilding.swap(true, Ordering::SeqCst) {
+      return;
+    }
+
+    // rebuilding takes a while, so it runs alongside following new blocks
+    let app_state = Arc::clone(&self.app_state);
+    let rebuilding = Arc::clone(&self.rebuilding);
+    tokio::spawn(async move {
+      let started = Instant::now();
+      match build_snapshot(&app_state, position).await {
+        Ok(snapshot) => {
+          info!("built snapshot for epoch {} in {}ms", position.epoch, started.elapsed().as_millis());
+          app_state.set_snapshot(snapshot);
+        },
+        // the next block will try again
+        Err(err) => warn!("could not build snapshot for epoch {}: {}", position.epoch, err.error),
+      }
+      rebuilding.store(false, Ordering::SeqCst);
+    });
+  }
+}
+
+async fn build_snapshot(app_state: &Arc<AppState>, position: ChainPosition) -> Result<ChainSnapshot, ApiError> {
+  let epoch = Epoch(position.epoch);
+  let mut validators = get_validators(app_state, epoch, None).await?;
+  set_commission_update_times(app_state, epoch, &mut validators).await?;
+  let total_staked = rpc::get_total_staked_tokens(app_state.get_client(), epoch).await?;
+  let proposals = get_all_proposal_data(Arc::clone(app_state), epoch).await?;
+  debug!("snapshot for epoch {}: {} validators, {} proposals", epoch, validators.len(), proposals.len());
+
+  Ok(ChainSnapshot {
+    height: position.height,
+    epoch,
+    updated_at: Time::now(),
+    validators,
+    total_staked,
+    proposals,
+  })
+}
+
+/// The websocket endpoint of a CometBFT rpc url, eg: http://localhost:26657 -> ws://localhost:26657/websocket
+fn websocket_url(rpc_url: &Url) -> Option<Url> {
+  let url = rpc_url.to_string();
+  let url = match (url.strip_prefix("https://"), url.strip_prefix("http://")) {
+    (Some(rest), _) => format!("wss://{}", rest),
+    (_, Some(rest)) => format!("ws://{}", rest),
+    _ => url.clone(),
+  };
+
+  Url::from_str(&format!("{}/websocket", url.trim_end_matches('/'))).ok()
+}
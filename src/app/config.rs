@@ -0,0 +1,410 @@
+use std::{
+  fmt,
+  fs,
+  net::{IpAddr, Ipv4Addr, SocketAddr},
+  path::PathBuf,
+  str::FromStr,
+};
+use axum::http::HeaderValue;
+use clap::{Args, Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use tendermint_rpc::Url;
+use tracing_subscriber::EnvFilter;
+
+/// Cosmos-sdk REST compatibility layer for a Namada full node
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Cli {
+  /// Path to a TOML config file; values given as flags or environment variables take precedence over it
+  #[arg(short, long, global = true, env = "CONFIG")]
+  pub config: Option<PathBuf>,
+
+  /// Defaults to `serve` with no overrides, so the server still starts when run without arguments
+  #[command(subcommand)]
+  pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+  /// Start the api server
+  Serve(ConfigOverrides),
+  /// Validate the configuration and print the resolved values without starting the server
+  CheckConfig(ConfigOverrides),
+  /// Print a config file containing the default values
+  DefaultConfig,
+  /// Index a range of past blocks into the indexer's store, then exit
+  #[cfg(feature = "indexer")]
+  Backfill(BackfillArgs),
+}
+
+#[cfg(feature = "indexer")]
+#[derive(Args)]
+pub struct BackfillArgs {
+  /// First height to index; defaults to indexer.start_height, or the node's earliest block
+  #[arg(long)]
+  pub from: Option<u64>,
+  /// Last height to index; defaults to the latest block
+  #[arg(long)]
+  pub to: Option<u64>,
+  #[command(flatten)]
+  pub overrides: ConfigOverrides,
+}
+
+/// Command line/environment overrides for individual config file values
+#[derive(Args, Default)]
+pub struct ConfigOverrides {
+  /// IP address to listen on, eg: 0.0.0.0
+  #[arg(long)]
+  pub listen_address: Option<IpAddr>,
+  /// Port to listen on
+  #[arg(short, long)]
+  pub port: Option<u16>,
+  /// Full node RPC url(s); repeat the flag or separate with commas
+  #[arg(long = "rpc", env = "RPC", value_delimiter = ',')]
+  pub rpc_urls: Vec<String>,
+  /// Allowed CORS origin(s), or '*' for any; repeat the flag or separate with commas
+  #[arg(long = "cors-origin", value_delimiter = ',')]
+  pub cors_origins: Vec<String>,
+  /// Directory of the Namada wallet used by the sdk
+  #[arg(long)]
+  pub wallet_dir: Option<PathBuf>,
+  /// Directory of the MASP parameters used by the sdk
+  #[arg(long)]
+  pub masp_dir: Option<PathBuf>,
+  /// Enable or disable in-memory caching of chain data
+  #[arg(long)]
+  pub cache_enabled: Option<bool>,
+  /// Maximum number of entries held by each cache
+  #[arg(long)]
+  pub cache_max_entries: Option<usize>,
+  /// Log level or filter directive, eg: 'info' or 'namada_ping_middleware=debug'
+  #[arg(long, env = "LOG_LEVEL")]
+  pub log_level: Option<String>,
+  /// Return synthetic valoper/valcons addresses for validators
+  #[arg(long, env = "COMPAT_ADDRESSES")]
+  pub compat_addresses: Option<bool>,
+  /// Report not ready when the node's latest block is older than this many seconds
+  #[arg(long)]
+  pub max_block_age_secs: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+  pub server: ServerConfig,
+  pub rpc: RpcConfig,
+  pub namada: NamadaConfig,
+  pub cache: CacheConfig,
+  pub follower: FollowerConfig,
+  pub indexer: IndexerConfig,
+  pub health: HealthConfig,
+  pub log: LogConfig,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+  pub listen_address: IpAddr,
+  pub port: u16,
+  pub cors_origins: Vec<String>,
+}
+
+impl Default for ServerConfig {
+  fn default() -> Self {
+    ServerConfig {
+      listen_address: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+      port: 1317,
+      cors_origins: vec!["*".to_string()],
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct RpcConfig {
+  // the first url is the primary node, the others are used when it is unhealthy
+  pub urls: Vec<String>,
+  pub health_check_interval_secs: u64,
+  // nodes further than this behind the highest node are considered unhealthy
+  pub max_lag_blocks: u64,
+}
+
+impl Default for RpcConfig {
+  fn default() -> Self {
+    RpcConfig {
+      urls: vec!["http://localhost:26657".to_string()],
+      health_check_interval_secs: 10,
+      max_lag_blocks: 5,
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct NamadaConfig {
+  pub wallet_dir: PathBuf,
+  pub masp_dir: PathBuf,
+  pub compat_addresses: bool,
+}
+
+impl Default for NamadaConfig {
+  fn default() -> Self {
+    NamadaConfig {
+      wallet_dir: "wallet".into(),
+      masp_dir: "masp".into(),
+      compat_addresses: false,
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+  pub enabled: bool,
+  pub max_entries: usize,
+}
+
+impl Default for CacheConfig {
+  fn default() -> Self {
+    CacheConfig { enabled: true, max_entries: 10_000 }
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct FollowerConfig {
+  // subscribe to new blocks over the primary node's websocket; falls back to polling if unavailable
+  pub websocket: bool,
+  pub poll_interval_ms: u64,
+}
+
+impl Default for FollowerConfig {
+  fn default() -> Self {
+    FollowerConfig { websocket: true, poll_interval_ms: 1000 }
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct IndexerConfig {
+  // only available when built with the 'indexer' feature
+  pub enabled: bool,
+  pub path: PathBuf,
+  // where a new index starts from, the latest block if unset; older blocks can be added with the backfill command
+  pub start_height: Option<u64>,
+  pub poll_interval_ms: u64,
+}
+
+impl Default for IndexerConfig {
+  fn default() -> Self {
+    IndexerConfig {
+      enabled: false,
+      path: "index.sqlite".into(),
+      start_height: None,
+      poll_interval_ms: 1000,
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthConfig {
+  // /ready fails when the node's latest block is older than this, ie: the chain or the node has stalled
+  pub max_block_age_secs: u64,
+}
+
+impl Default for HealthConfig {
+  fn default() -> Self {
+    HealthConfig { max_block_age_secs: 60 }
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+  pub level: String,
+}
+
+impl Default for LogConfig {
+  fn default() -> Self {
+    LogConfig { level: "info".to_string() }
+  }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+  Read(PathBuf, std::io::Error),
+  Parse(PathBuf, toml::de::Error),
+  Invalid(Vec<String>),
+  Startup(String),
+}
+
+impl fmt::Display for ConfigError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ConfigError::Read(path, err) => write!(f, "could not read config file {}: {}", path.display(), err),
+      ConfigError::Parse(path, err) => write!(f, "could not parse config file {}: {}", path.display(), err),
+      ConfigError::Invalid(problems) => {
+        write!(f, "invalid configuration:")?;
+        for problem in problems {
+          write!(f, "\n  - {}", problem)?;
+        }
+        Ok(())
+      },
+      ConfigError::Startup(err) => write!(f, "startup failed: {}", err),
+    }
+  }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl AppConfig {
+  /// Builds the config from defaults, then the config file (if any), then the command line/environment overrides,
+  /// and validates the result
+  pub fn load(path: Option<&PathBuf>, overrides: ConfigOverrides) -> Result<Self, ConfigError> {
+    let mut config = match path {
+      Some(path) => {
+        let contents = fs::read_to_string(path).map_err(|err| ConfigError::Read(path.clone(), err))?;
+        toml::from_str::<AppConfig>(&contents).map_err(|err| ConfigError::Parse(path.clone(), err))?
+      },
+      None => AppConfig::default(),
+    };
+
+    config.apply(overrides);
+    config.validate()?;
+
+    Ok(config)
+  }
+
+  fn apply(&mut self, overrides: ConfigOverrides) {
+    if let Some(listen_address) = overrides.listen_address {
+      self.server.listen_address = listen_address;
+    }
+    if let Some(port) = overrides.port {
+      self.server.port = port;
+    }
+    if !overrides.rpc_urls.is_empty() {
+      self.rpc.urls = overrides.rpc_urls;
+    }
+    if !overrides.cors_origins.is_empty() {
+      self.server.cors_origins = overrides.cors_origins;
+    }
+    if let Some(wallet_dir) = overrides.wallet_dir {
+      self.namada.wallet_dir = wallet_dir;
+    }
+    if let Some(masp_dir) = overrides.masp_dir {
+      self.namada.masp_dir = masp_dir;
+    }
+    if let Some(enabled) = overrides.cache_enabled {
+      self.cache.enabled = enabled;
+    }
+    if let Some(max_entries) = overrides.cache_max_entries {
+      self.cache.max_entries = max_entries;
+    }
+    if let Some(level) = overrides.log_level {
+      self.log.level = level;
+    }
+    if let Some(compat_addresses) = overrides.compat_addresses {
+      self.namada.compat_addresses = compat_addresses;
+    }
+    if let Some(max_block_age_secs) = overrides.max_block_age_secs {
+      self.health.max_block_age_secs = max_block_age_secs;
+    }
+  }
+
+  /// Checks every value and reports all of the problems at once rather than stopping at the first one
+  fn validate(&self) -> Result<(), ConfigError> {
+    let mut problems = Vec::new();
+
+    if self.server.port == 0 {
+      problems.push("server.port must not be 0".to_string());
+    }
+
+    if self.rpc.urls.is_empty() {
+      problems.push("rpc.urls must contain at least one url".to_string());
+    }
+    for url in &self.rpc.urls {
+      match Url::from_str(url) {
+        Ok(_) => {},
+        Err(err) => problems.push(format!("rpc url '{}' is invalid: {}", url, err)),
+      }
+    }
+
+    if self.rpc.health_check_interval_secs == 0 {
+      problems.push("rpc.health_check_interval_secs must be greater than 0".to_string());
+    }
+
+    if self.server.cors_origins.is_empty() {
+      problems.push("server.cors_origins must contain at least one origin (use '*' to allow any)".to_string());
+    }
+    for origin in &self.server.cors_origins {
+      if origin != "*" && HeaderValue::from_str(origin).is_err() {
+        problems.push(format!("cors origin '{}' is not a valid header value", origin));
+      }
+    }
+
+    // several instances can run on the same host, but they mustn't share the sdk's working directories
+    if self.namada.wallet_dir == self.namada.masp_dir {
+      problems.push(format!("namada.wallet_dir and namada.masp_dir must be different directories (both are {})", self.namada.wallet_dir.display()));
+    }
+    for (name, dir) in [("namada.wallet_dir", &self.namada.wallet_dir), ("namada.masp_dir", &self.namada.masp_dir)] {
+      if dir.exists() && !dir.is_dir() {
+        problems.push(format!("{} {} exists but is not a directory", name, dir.display()));
+      }
+    }
+
+    if self.cache.enabled && self.cache.max_entries == 0 {
+      problems.push("cache.max_entries must be greater than 0 when the cache is enabled".to_string());
+    }
+    if self.follower.poll_interval_ms == 0 {
+      problems.push("follower.poll_interval_ms must be greater than 0".to_string());
+    }
+
+    if self.indexer.enabled {
+      if !cfg!(feature = "indexer") {
+        problems.push("indexer.enabled requires building with the 'indexer' feature".to_string());
+      }
+      if self.indexer.start_height == Some(0) {
+        problems.push("indexer.start_height must be at least 1".to_string());
+      }
+      if self.indexer.poll_interval_ms == 0 {
+        problems.push("indexer.poll_interval_ms must be greater than 0".to_string());
+      }
+      if self.indexer.path.is_dir() {
+        problems.push(format!("indexer.path {} is a directory", self.indexer.path.display()));
+      }
+    }
+
+    if self.health.max_block_age_secs == 0 {
+      problems.push("health.max_block_age_secs must be greater than 0".to_string());
+    }
+
+    if let Err(err) = EnvFilter::try_new(&self.log.level) {
+      problems.push(format!("log.level '{}' is invalid: {}", self.log.level, err));
+    }
+
+    if problems.is_empty() {
+      Ok(())
+    } else {
+      Err(ConfigError::Invalid(problems))
+    }
+  }
+
+  pub fn listen_address(&self) -> SocketAddr {
+    SocketAddr::new(self.server.listen_address, self.server.port)
+  }
+
+  /// Rpc urls in order of preference; only call on a validated config
+  pub fn rpc_urls(&self) -> Vec<Url> {
+    self.rpc.urls.iter().filter_map(|url| Url::from_str(url).ok()).collect()
+  }
+
+  /// None means any origin is allowed
+  pub fn cors_origins(&self) -> Option<Vec<HeaderValue>> {
+    if self.server.cors_origins.iter().any(|origin| origin == "*") {
+      return None;
+    }
+
+    Some(self.server.cors_origins.iter().filter_map(|origin| HeaderValue::from_str(origin).ok()).collect())
+  }
+}
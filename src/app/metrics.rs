@@ -0,0 +1,264 @@
+use std::{
+  collections::BTreeMap,
+  fmt::Write,
+  sync::{Arc, RwLock},
+  time::{Duration, Instant},
+};
+use axum::{
+  extract::{MatchedPath, Request, State},
+  middleware::Next,
+  response::Response,
+};
+
+use crate::app::{app_state::AppState, chain_follower::ChainSnapshot};
+use crate::model::{gov::CosmosProposalStatus, uptime::UptimeResponse};
+
+const PREFIX: &str = "namada_middleware";
+/// Upper bounds (in seconds) of the request latency histogram buckets
+const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+/// Most unimplemented path patterns counted separately; the rest are counted together under `OTHER_PATTERN`
+const MAX_UNIMPLEMENTED_PATTERNS: usize = 200;
+const OTHER_PATTERN: &str = "other";
+
+#[derive(Default)]
+struct Histogram {
+  // non-cumulative counts per bucket; the +Inf bucket is `count`
+  buckets: [u64; LATENCY_BUCKETS.len()],
+  sum: f64,
+  count: u64,
+}
+
+impl Histogram {
+  fn observe(&mut self, seconds: f64) {
+    if let Some(bucket) = LATENCY_BUCKETS.iter().position(|bound| seconds <= *bound) {
+      self.buckets[bucket] += 1;
+    }
+    self.sum += seconds;
+    self.count += 1;
+  }
+}
+
+/// Per route request counts and latencies, rendered in the Prometheus text format along with the
+/// rpc, cache and chain stats the rest of the app already keeps
+#[derive(Default)]
+pub struct Metrics {
+  // (method, route, status) -> count
+  requests: RwLock<BTreeMap<(String, String, u16), u64>>,
+  // (method, route) -> latency
+  latencies: RwLock<BTreeMap<(String, String), Histogram>>,
+  // Cosmos route pattern -> requests for it, for the Cosmos endpoints that aren't implemented
+  unimplemented: RwLock<BTreeMap<String, u64>>,
+}
+
+impl Metrics {
+  pub fn new() -> Self {
+    Metrics::default()
+  }
+
+  pub fn record_request(&self, method: &str, route: &str, status: u16, duration: Duration) {
+    *self.requests.write().unwrap()
+      .entry((method.to_string(), route.to_string(), status))
+      .or_default() += 1;
+    self.latencies.write().unwrap()
+      .entry((method.to_string(), route.to_string()))
+      .or_default()
+      .observe(duration.as_secs_f64());
+  }
+
+  /// The patterns come from client supplied paths, so their number is capped to bound memory and label cardinality
+  pub fn record_unimplemented(&self, route: &str) {
+    let mut unimplemented = self.unimplemented.write().unwrap();
+    let route = match unimplemented.contains_key(route) || unimplemented.len() < MAX_UNIMPLEMENTED_PATTERNS {
+      true => route,
+      false => OTHER_PATTERN,
+    };
+    *unimplemented.entry(route.to_string()).or_default() += 1;
+  }
+
+  /// Renders every metric in the Prometheus text exposition format
+  pub fn render(&self, app_state: &AppState) -> String {
+    let mut out = String::new();
+
+    family(&mut out, "http_requests_total", "counter", "HTTP requests handled, by route and status");
+    for ((method, route, status), count) in self.requests.read().unwrap().iter() {
+      sample(&mut out, "http_requests_total", &[("method", method), ("route", route), ("status", &status.to_string())], *count as f64);
+    }
+
+    family(&mut out, "http_request_duration_seconds", "histogram", "HTTP request latency, by route");
+    for ((method, route), histogram) in self.latencies.read().unwrap().iter() {
+      let mut cumulative = 0;
+      for (bound, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets.iter()) {
+        cumulative += count;
+        let le = bound.to_string();
+        sample(&mut out, "http_request_duration_seconds_bucket", &[("method", method), ("route", route), ("le", &le)], cumulative as f64);
+      }
+      sample(&mut out, "http_request_duration_seconds_bucket", &[("method", method), ("route", route), ("le", "+Inf")], histogram.count as f64);
+      sample(&mut out, "http_request_duration_seconds_sum", &[("method", method), ("route", route)], histogram.sum);
+      sample(&mut out, "http_request_duration_seconds_count", &[("method", method), ("route", route)], histogram.count as f64);
+    }
+
+    family(&mut out, "unimplemented_requests_total", "counter", "Requests for Cosmos endpoints that aren't implemented, by path pattern");
+    for (route, count) in self.unimplemented.read().unwrap().iter() {
+      sample(&mut out, "unimplemented_requests_total", &[("path", route)], *count as f64);
+    }
+
+    let rpc_calls = app_state.get_client().call_stats();
+    family(&mut out, "rpc_requests_total", "counter", "Requests to the upstream rpc nodes, by query");
+    for call in &rpc_calls {
+      sample(&mut out, "rpc_requests_total", &[("query", &call.query)], call.calls as f64);
+    }
+    family(&mut out, "rpc_errors_total", "counter", "Failed requests to the upstream rpc nodes (after failover), by query");
+    for call in &rpc_calls {
+      sample(&mut out, "rpc_errors_total", &[("query", &call.query)], call.errors as f64);
+    }
+
+    family(&mut out, "rpc_node_healthy", "gauge", "Whether an rpc node passed its last health check");
+    for node in app_state.get_client().node_statuses() {
+      sample(&mut out, "rpc_node_healthy", &[("url", &node.url)], if node.healthy { 1.0 } else { 0.0 });
+    }
+
+    let cache = &app_state.response_cache;
+    let categories = cache.category_counts();
+    family(&mut out, "cache_hits_total", "counter", "Response cache hits, by category");
+    for (category, hits, _) in &categories {
+      sample(&mut out, "cache_hits_total", &[("category", category)], *hits as f64);
+    }
+    family(&mut out, "cache_misses_total", "counter", "Response cache misses, by category");
+    for (category, _, misses) in &categories {
+      sample(&mut out, "cache_misses_total", &[("category", category)], *misses as f64);
+    }
+    family(&mut out, "cache_entries", "gauge", "Responses currently cached");
+    sample(&mut out, "cache_entries", &[], cache.entry_count() as f64);
+    family(&mut out, "cache_invalidations_total", "counter", "Cached responses dropped on a new block or epoch");
+    sample(&mut out, "cache_invalidations_total", &[], cache.invalidation_count() as f64);
+
+    // the position is unknown until the chain follower has seen a block (or after it failed to query one)
+    if let Some(position) = cache.position() {
+      family(&mut out, "latest_block_height", "gauge", "Latest block height seen by the chain follower");
+      sample(&mut out, "latest_block_height", &[], position.height as f64);
+      family(&mut out, "latest_epoch", "gauge", "Latest epoch seen by the chain follower");
+      sample(&mut out, "latest_epoch", &[], position.epoch as f64);
+    }
+
+    out
+  }
+}
+
+/// Renders chain gauges from the chain follower's snapshot, and missed blocks from the uptime over the default window
+/// Either may be missing (eg: right after startup), in which case its gauges are left out
+pub fn render_chain(snapshot: Option<&ChainSnapshot>, uptime: Option<&UptimeResponse>) -> String {
+  let mut out = String::new();
+
+  if let Some(snapshot) = snapshot {
+    family(&mut out, "snapshot_epoch", "gauge", "Epoch the validator and proposal gauges were computed at");
+    sample(&mut out, "snapshot_epoch", &[], snapshot.epoch.0 as f64);
+    family(&mut out, "total_bonded_tokens", "gauge", "Total bonded NAM");
+    sample(&mut out, "total_bonded_tokens", &[], native_amount(&snapshot.total_staked.to_string_native()));
+
+    family(&mut out, "validator_tokens", "gauge", "Validator stake in NAM");
+    for validator in &snapshot.validators {
+      // the validators' tokens are already in whole NAM
+      sample(&mut out, "validator_tokens", &[("validator", &validator.operator_address)], native_amount(&validator.tokens.to_string()));
+    }
+    family(&mut out, "validator_status", "gauge", "Validator bond status; 1 for the validator's current status");
+    for validator in &snapshot.validators {
+      let status = format!("{:?}", validator.status);
+      sample(&mut out, "validator_status", &[("validator", &validator.operator_address), ("status", &status)], 1.0);
+    }
+    family(&mut out, "validator_jailed", "gauge", "Whether a validator is jailed");
+    for validator in &snapshot.validators {
+      sample(&mut out, "validator_jailed", &[("validator", &validator.operator_address)], if validator.jailed { 1.0 } else { 0.0 });
+    }
+    family(&mut out, "validator_commission_rate", "gauge", "Validator commission rate");
+    for validator in &snapshot.validators {
+      let rate = validator.commission.commission_rates.rate.to_string();
+      sample(&mut out, "validator_commission_rate", &[("validator", &validator.operator_address)], native_amount(&rate));
+    }
+
+    let active_proposals: Vec<_> = snapshot.proposals.iter()
+      .filter(|proposal| matches!(proposal.status, CosmosProposalStatus::PROPOSAL_STATUS_VOTING_PERIOD))
+      .collect();
+    family(&mut out, "active_proposals", "gauge", "Proposals in their voting period");
+    sample(&mut out, "active_proposals", &[], active_proposals.len() as f64);
+    family(&mut out, "proposal_tally_ratio", "gauge", "Share of the voting power tallied for each option, for proposals in their voting period, as of the snapshot (refreshed once per epoch)");
+    for proposal in active_proposals {
+      let tally = &proposal.final_tally_result;
+      let id = proposal.proposal.id.to_string();
+      let (yes, no) = (native_amount(&tally.yes), native_amount(&tally.no));
+      let total = yes + no;
+      for (option, power) in [("yes", yes), ("no", no)] {
+        sample(&mut out, "proposal_tally_ratio", &[("proposal_id", &id), ("option", option)], if total > 0.0 { power / total } else { 0.0 });
+      }
+    }
+  }
+
+  if let Some(uptime) = uptime {
+    family(&mut out, "validator_missed_blocks", "gauge", "Blocks a validator didn't sign over the uptime window");
+    for validator in &uptime.validators {
+      let address = validator.operator_address.as_ref().unwrap_or(&validator.consensus_address);
+      let missed = validator.missed.parse().unwrap_or(0.0);
+      sample(&mut out, "validator_missed_blocks", &[("validator", address), ("window", &uptime.window)], missed);
+    }
+  }
+
+  out
+}
+
+/// Middleware recording each request's count and latency under its route pattern, eg: '/cosmos/gov/v1/proposals/:id'
+pub async fn track_metrics(State(app_state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+  let started = Instant::now();
+  let method = request.method().to_string();
+  // unmatched paths are lumped together, so that random urls don't each get their own series
+  let route = request.extensions().get::<MatchedPath>()
+    .map(|path| path.as_str().to_string())
+    .unwrap_or_else(|| "unmatched".to_string());
+
+  let response = next.run(request).await;
+  app_state.metrics.record_request(&method, &route, response.status().as_u16(), started.elapsed());
+
+  response
+}
+
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+  let _ = writeln!(out, "# HELP {}_{} {}", PREFIX, name, help);
+  let _ = writeln!(out, "# TYPE {}_{} {}", PREFIX, name, kind);
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+  let labels = labels.iter()
+    .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+    .collect::<Vec<String>>();
+  match labels.is_empty() {
+    true => { let _ = writeln!(out, "{}_{} {}", PREFIX, name, value); },
+    false => { let _ = writeln!(out, "{}_{}{{{}}} {}", PREFIX, name, labels.join(","), value); },
+  }
+}
+
+fn escape_label(value: &str) -> String {
+  value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Gauges are floats, so decimal amounts/rates are reported as-is rather than in base units
+fn native_amount(amount: &str) -> f64 {
+  amount.parse().unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn unimplemented_patterns_are_capped() {
+    let metrics = Metrics::new();
+    for pattern in 0..MAX_UNIMPLEMENTED_PATTERNS + 5 {
+      metrics.record_unimplemented(&format!("/cosmos/pattern{}", pattern));
+    }
+    // patterns already counted keep their own count
+    metrics.record_unimplemented("/cosmos/pattern0");
+
+    let unimplemented = metrics.unimplemented.read().unwrap();
+    assert_eq!(unimplemented.len(), MAX_UNIMPLEMENTED_PATTERNS + 1);
+    assert_eq!(unimplemented.get(OTHER_PATTERN), Some(&5));
+    assert_eq!(unimplemented.get("/cosmos/pattern0"), Some(&2));
+  }
+}
@@ -1 +1,9 @@
+pub mod address_map;
 pub mod app_state;
+pub mod chain_follower;
+pub mod config;
+pub mod metrics;
+pub mod proposal_cache;
+pub mod response_cache;
+pub mod rpc_pool;
+pub mod signing_history;
@@ -0,0 +1,51 @@
+use std::{
+  collections::HashMap,
+  sync::RwLock,
+};
+use namada_sdk::governance::{storage::proposal::StorageProposal, utils::ProposalResult};
+
+use crate::app::config::CacheConfig;
+
+/// Governance data that can't change once it has been written to storage, kept in memory so that
+/// listing proposals doesn't query every proposal from the full node on each request
+#[derive(Default)]
+pub struct ProposalCache {
+  enabled: bool,
+  max_entries: usize,
+  // proposals are immutable after submission
+  proposals: RwLock<HashMap<u64, StorageProposal>>,
+  // results are only cached once voting has ended and the tally is final
+  final_results: RwLock<HashMap<u64, ProposalResult>>,
+}
+
+impl ProposalCache {
+  pub fn new(config: &CacheConfig) -> Self {
+    ProposalCache {
+      enabled: config.enabled,
+      max_entries: config.max_entries,
+      ..Default::default()
+    }
+  }
+
+  pub fn get_proposal(&self, id: u64) -> Option<StorageProposal> {
+    self.proposals.read().unwrap().get(&id).cloned()
+  }
+
+  pub fn insert_proposal(&self, proposal: StorageProposal) {
+    let mut proposals = self.proposals.write().unwrap();
+    if self.enabled && proposals.len() < self.max_entries {
+      proposals.insert(proposal.id, proposal);
+    }
+  }
+
+  pub fn get_final_result(&self, id: u64) -> Option<ProposalResult> {
+    self.final_results.read().unwrap().get(&id).cloned()
+  }
+
+  pub fn insert_final_result(&self, id: u64, result: ProposalResult) {
+    let mut final_results = self.final_results.write().unwrap();
+    if self.enabled && final_results.len() < self.max_entries {
+      final_results.insert(id, result);
+    }
+  }
+}
@@ -0,0 +1,200 @@
+use std::{
+  any::Any,
+  collections::{BTreeMap, HashMap},
+  sync::{Arc, RwLock},
+};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::app::config::CacheConfig;
+
+/// How long a cached response stays valid
+#[derive(Clone, Copy, PartialEq)]
+pub enum CacheScope {
+  // until the next epoch, eg: params, validator metadata and stake
+  Epoch,
+  // until the next block, eg: anything that reports the latest height
+  Block,
+}
+
+/// The latest block height and epoch observed on chain; cached entries are tagged with the position they were computed at
+#[derive(Clone, Copy, PartialEq, Default)]
+pub struct ChainPosition {
+  pub height: u64,
+  pub epoch: u64,
+}
+
+struct CacheEntry {
+  value: Arc<dyn Any + Send + Sync>,
+  scope: CacheScope,
+  position: ChainPosition,
+}
+
+#[derive(Default)]
+struct CacheCounters {
+  hits: u64,
+  misses: u64,
+}
+
+/// In-memory cache of computed responses, keyed by a category (usually the endpoint) and the request parameters
+/// The chain follower reports each new block/epoch, so that stale entries are dropped as soon as it is observed
+pub struct ResponseCache {
+  enabled: bool,
+  max_entries: usize,
+  entries: RwLock<HashMap<String, CacheEntry>>,
+  // None until the chain position is known, or after it failed to update; nothing is cached in the meantime
+  position: RwLock<Option<ChainPosition>>,
+  counters: RwLock<BTreeMap<&'static str, CacheCounters>>,
+  invalidations: RwLock<u64>,
+}
+
+#[derive(Serialize)]
+pub struct CacheStats {
+  pub enabled: bool,
+  pub entries: usize,
+  pub height: Option<String>,
+  pub epoch: Option<String>,
+  pub invalidations: String,
+  pub categories: Vec<CategoryStats>,
+}
+
+#[derive(Serialize)]
+pub struct CategoryStats {
+  pub category: String,
+  pub hits: String,
+  pub misses: String,
+  pub hit_rate: f64,
+}
+
+impl ResponseCache {
+  pub fn new(config: &CacheConfig) -> Self {
+    ResponseCache {
+      enabled: config.enabled,
+      max_entries: config.max_entries,
+      entries: RwLock::new(HashMap::new()),
+      position: RwLock::new(None),
+      counters: RwLock::new(BTreeMap::new()),
+      invalidations: RwLock::new(0),
+    }
+  }
+
+  pub fn position(&self) -> Option<ChainPosition> {
+    *self.position.read().unwrap()
+  }
+
+  /// Returns the cached value if it is still valid for the current chain position, and counts the hit or miss
+  pub fn get<T: Clone + 'static>(&self, category: &'static str, key: &str) -> Option<T> {
+    let value = self.position().and_then(|position| {
+      let entries = self.entries.read().unwrap();
+      let entry = entries.get(&entry_key(category, key))?;
+      if !entry_is_current(entry, &position) {
+        return None;
+      }
+      entry.value.downcast_ref::<T>().cloned()
+    });
+
+    let mut counters = self.counters.write().unwrap();
+    let counter = counters.entry(category).or_default();
+    match value {
+      Some(_) => counter.hits += 1,
+      None => counter.misses += 1,
+    }
+
+    value
+  }
+
+  /// Caches a value computed at `position`; values computed before the latest observed block/epoch are discarded
+  pub fn insert<T: Send + Sync + 'static>(&self, category: &'static str, key: &str, scope: CacheScope, position: Option<ChainPosition>, value: T) {
+    let (Some(position), Some(current)) = (position, self.position()) else {
+      return;
+    };
+    let entry = CacheEntry { value: Arc::new(value), scope, position };
+    if !self.enabled || !entry_is_current(&entry, &current) {
+      return;
+    }
+
+    let mut entries = self.entries.write().unwrap();
+    if entries.len() >= self.max_entries {
+      // drop whatever has gone stale before giving up on caching this value
+      entries.retain(|_, entry| entry_is_current(entry, &current));
+      if entries.len() >= self.max_entries {
+        return;
+      }
+    }
+    entries.insert(entry_key(category, key), entry);
+  }
+
+  /// Records the latest chain position, dropping block scoped entries on a new block and everything on a new epoch
+  pub fn observe(&self, position: Option<ChainPosition>) {
+    let previous = {
+      let mut current = self.position.write().unwrap();
+      std::mem::replace(&mut *current, position)
+    };
+    if previous == position {
+      return;
+    }
+
+    let mut entries = self.entries.write().unwrap();
+    let before = entries.len();
+    match position {
+      Some(position) => entries.retain(|_, entry| entry_is_current(entry, &position)),
+      None => entries.clear(),
+    }
+
+    let dropped = before - entries.len();
+    if dropped > 0 {
+      *self.invalidations.write().unwrap() += dropped as u64;
+      debug!("dropped {} stale cache entries", dropped);
+    }
+  }
+
+  /// (category, hits, misses) for each category requested so far
+  pub fn category_counts(&self) -> Vec<(&'static str, u64, u64)> {
+    self.counters.read().unwrap().iter()
+      .map(|(category, counter)| (*category, counter.hits, counter.misses))
+      .collect()
+  }
+
+  pub fn entry_count(&self) -> usize {
+    self.entries.read().unwrap().len()
+  }
+
+  pub fn invalidation_count(&self) -> u64 {
+    *self.invalidations.read().unwrap()
+  }
+
+  pub fn stats(&self) -> CacheStats {
+    let position = self.position();
+    let categories = self.category_counts().into_iter()
+      .map(|(category, hits, misses)| {
+        let total = hits + misses;
+        CategoryStats {
+          category: category.to_string(),
+          hits: hits.to_string(),
+          misses: misses.to_string(),
+          hit_rate: if total == 0 { 0.0 } else { hits as f64 / total as f64 },
+        }
+      })
+      .collect();
+
+    CacheStats {
+      enabled: self.enabled,
+      entries: self.entry_count(),
+      height: position.map(|position| position.height.to_string()),
+      epoch: position.map(|position| position.epoch.to_string()),
+      invalidations: self.invalidation_count().to_string(),
+      categories,
+    }
+  }
+}
+
+fn entry_key(category: &str, key: &str) -> String {
+  format!("{}/{}", category, key)
+}
+
+fn entry_is_current(entry: &CacheEntry, position: &ChainPosition) -> bool {
+  match entry.scope {
+    CacheScope::Epoch => entry.position.epoch == position.epoch,
+    CacheScope::Block => entry.position == *position,
+  }
+}
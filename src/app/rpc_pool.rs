@@ -0,0 +1,246 @@
+use std::{
+  collections::BTreeMap,
+  sync::{Arc, RwLock},
+  time::{Duration, Instant},
+};
+use async_trait::async_trait;
+use serde::Serialize;
+use tendermint_rpc::{
+  error::ErrorDetail, Client, Error, HttpClient, SimpleRequest, Url,
+};
+use tracing::{debug, info, warn};
+
+use crate::app::config::{ConfigError, RpcConfig};
+
+/// A set of CometBFT rpc endpoints that can be used like a single client
+/// Each request goes to the first healthy node (in the configured order); if the node fails to respond the
+/// request is retried on the next one
+#[derive(Clone)]
+pub struct RpcPool {
+  nodes: Arc<Vec<RpcNode>>,
+  max_lag_blocks: u64,
+  // query -> (calls, errors)
+  calls: Arc<RwLock<BTreeMap<String, (u64, u64)>>>,
+}
+
+struct RpcNode {
+  url: Url,
+  client: HttpClient,
+  health: RwLock<NodeHealth>,
+}
+
+#[derive(Clone, Default)]
+struct NodeHealth {
+  // nodes are assumed healthy until a check or a request says otherwise
+  unhealthy: bool,
+  catching_up: bool,
+  latest_height: u64,
+  last_error: Option<String>,
+  last_checked: Option<Instant>,
+}
+
+/// How often a query was sent to the pool, and how often it failed on every node
+pub struct RpcCallStats {
+  pub query: String,
+  pub calls: u64,
+  pub errors: u64,
+}
+
+/// Health of a single node, as of the last check
+#[derive(Serialize, Clone)]
+pub struct RpcNodeStatus {
+  pub url: String,
+  pub healthy: bool,
+  pub catching_up: bool,
+  pub latest_height: String,
+  pub lag: String,
+  pub last_error: Option<String>,
+  pub seconds_since_check: Option<u64>,
+}
+
+impl RpcPool {
+  pub fn new(config: &RpcConfig, urls: Vec<Url>) -> Result<Self, ConfigError> {
+    let mut nodes = Vec::new();
+    for url in urls {
+      let client = HttpClient::new(url.clone())
+        .map_err(|err| ConfigError::Startup(format!("could not create rpc client for {}: {}", url, err)))?;
+      nodes.push(RpcNode { url, client, health: RwLock::new(NodeHealth::default()) });
+    }
+
+    if nodes.is_empty() {
+      return Err(ConfigError::Startup("no rpc url configured".to_string()));
+    }
+
+    Ok(RpcPool {
+      nodes: Arc::new(nodes),
+      max_lag_blocks: config.max_lag_blocks,
+      calls: Arc::new(RwLock::new(BTreeMap::new())),
+    })
+  }
+
+  /// Checks the status of every node; a node is healthy if it responds, isn't catching up, and isn't lagging
+  /// more than `max_lag_blocks` behind the highest node
+  pub async fn check_health(&self) {
+    let statuses = futures::future::join_all(self.nodes.iter().map(|node| node.client.status())).await;
+    let max_height = statuses.iter()
+      .filter_map(|status| status.as_ref().ok())
+      .map(|status| status.sync_info.latest_block_height.value())
+      .max()
+      .unwrap_or(0);
+
+    for (node, status) in self.nodes.iter().zip(statuses) {
+      let mut health = node.health.write().unwrap();
+      let was_unhealthy = health.unhealthy;
+      health.last_checked = Some(Instant::now());
+
+      match status {
+        Ok(status) => {
+          health.latest_height = status.sync_info.latest_block_height.value();
+          health.catching_up = status.sync_info.catching_up;
+          let lag = max_height.saturating_sub(health.latest_height);
+          health.unhealthy = health.catching_up || lag > self.max_lag_blocks;
+          health.last_error = match (health.catching_up, lag > self.max_lag_blocks) {
+            (true, _) => Some("node is catching up".to_string()),
+            (false, true) => Some(format!("node is {} blocks behind", lag)),
+            (false, false) => None,
+          };
+        },
+        Err(err) => {
+          health.unhealthy = true;
+          health.last_error = Some(err.to_string());
+        },
+      }
+
+      if health.unhealthy != was_unhealthy {
+        match &health.last_error {
+          Some(err) => warn!("rpc node {} is unhealthy: {}", node.url, err),
+          None => info!("rpc node {} is healthy again", node.url),
+        }
+      }
+    }
+  }
+
+  /// Re-checks node health in the background every `interval`
+  pub fn spawn_health_checks(&self, interval: Duration) {
+    let pool = self.clone();
+    tokio::spawn(async move {
+      let mut ticker = tokio::time::interval(interval);
+      loop {
+        ticker.tick().await;
+        pool.check_health().await;
+      }
+    });
+  }
+
+  pub fn node_statuses(&self) -> Vec<RpcNodeStatus> {
+    let healths: Vec<NodeHealth> = self.nodes.iter().map(|node| node.health.read().unwrap().clone()).collect();
+    let max_height = healths.iter().map(|health| health.latest_height).max().unwrap_or(0);
+
+    self.nodes.iter().zip(healths)
+      .map(|(node, health)| RpcNodeStatus {
+        url: node.url.to_string(),
+        healthy: !health.unhealthy,
+        catching_up: health.catching_up,
+        latest_height: health.latest_height.to_string(),
+        lag: max_height.saturating_sub(health.latest_height).to_string(),
+        last_error: health.last_error,
+        seconds_since_check: health.last_checked.map(|checked| checked.elapsed().as_secs()),
+      })
+      .collect()
+  }
+
+  pub fn call_stats(&self) -> Vec<RpcCallStats> {
+    self.calls.read().unwrap().iter()
+      .map(|(query, (calls, errors))| RpcCallStats { query: query.clone(), calls: *calls, errors: *errors })
+      .collect()
+  }
+
+  fn record_call(&self, query: String, failed: bool) {
+    let mut calls = self.calls.write().unwrap();
+    let (count, errors) = calls.entry(query).or_default();
+    *count += 1;
+    if failed {
+      *errors += 1;
+    }
+  }
+
+  /// Healthy nodes first, in configured order, followed by the unhealthy ones as a last resort
+  fn candidates(&self) -> Vec<&RpcNode> {
+    let (mut healthy, unhealthy): (Vec<&RpcNode>, Vec<&RpcNode>) = self.nodes.iter()
+      .partition(|node| !node.health.read().unwrap().unhealthy);
+    healthy.extend(unhealthy);
+    healthy
+  }
+}
+
+#[async_trait]
+impl Client for RpcPool {
+  async fn perform<R>(&self, request: R) -> Result<R::Output, Error>
+  where
+    R: SimpleRequest,
+  {
+    let query = query_label(&request);
+    let result = self.perform_with_failover(request).await;
+    self.record_call(query, result.is_err());
+    result
+  }
+}
+
+impl RpcPool {
+  async fn perform_with_failover<R>(&self, request: R) -> Result<R::Output, Error>
+  where
+    R: SimpleRequest,
+  {
+    let candidates = self.candidates();
+    if candidates.len() == 1 {
+      return candidates[0].client.perform(request).await;
+    }
+
+    // requests aren't Clone, but they are (de)serializable, so each retry gets a copy from the json form
+    let json = serde_json::to_value(&request).map_err(Error::serde)?;
+    let mut last_error = None;
+
+    for node in candidates {
+      let request: R = serde_json::from_value(json.clone()).map_err(Error::serde)?;
+      match node.client.perform(request).await {
+        Ok(response) => return Ok(response),
+        // the node responded, it just didn't like the request; another node won't either
+        Err(err) if matches!(err.detail(), ErrorDetail::Response(_)) => return Err(err),
+        Err(err) => {
+          debug!("rpc request to {} failed, trying the next node: {}", node.url, err);
+          // take the node out of rotation until the next health check
+          {
+            let mut health = node.health.write().unwrap();
+            health.unhealthy = true;
+            health.last_error = Some(err.to_string());
+          }
+          last_error = Some(err);
+        },
+      }
+    }
+
+    // there's always at least one node, so there's always an error here
+    Err(last_error.unwrap_or_else(|| Error::client_internal("no rpc nodes available".to_string())))
+  }
+}
+
+/// Label for a request in the call stats: the rpc method, or for abci queries (which is how every namada_sdk
+/// query is sent) the query path up to its first parameter, eg: '/vp/pos/validator/stake'
+fn query_label<R: SimpleRequest>(request: &R) -> String {
+  let method = request.method().to_string();
+  if method != "abci_query" {
+    return method;
+  }
+
+  let path = serde_json::to_value(request).ok()
+    .and_then(|json| json.get("path").and_then(|path| path.as_str()).map(str::to_string))
+    .unwrap_or_default();
+  let segments: Vec<&str> = path.split('/')
+    .filter(|segment| !segment.is_empty())
+    // addresses, heights, epochs and storage keys would make a label per value
+    .take_while(|segment| segment.len() <= 32 && !segment.contains(|c: char| c.is_ascii_digit() || c == '#'))
+    .take(4)
+    .collect();
+
+  format!("/{}", segments.join("/"))
+}
@@ -0,0 +1,90 @@
+use std::{
+  collections::VecDeque,
+  sync::{Arc, RwLock},
+};
+use tendermint::Hash;
+use tokio::sync::{Mutex, MutexGuard};
+
+/// Most recent committed blocks whose signatures are kept, ie: the largest uptime window
+pub const SIGNING_HISTORY_BLOCKS: usize = 1000;
+
+/// Who proposed and who signed a committed block, as hex consensus addresses
+pub struct BlockSigning {
+  pub height: u64,
+  pub proposer: String,
+  // like the Cosmos slashing module, a nil vote counts as signed
+  pub signed: Vec<String>,
+  pub missed: Vec<String>,
+}
+
+/// A rolling record of the signatures of the most recent committed blocks, shared by all uptime requests, so that
+/// each block is only fetched once whatever windows are requested
+pub struct SigningHistory {
+  // ascending heights, without gaps
+  blocks: RwLock<VecDeque<Arc<BlockSigning>>>,
+  // hex consensus addresses of the last validator set seen, in set order, by validators hash
+  validator_set: RwLock<Option<(Hash, Arc<Vec<String>>)>>,
+  // held while new blocks are recorded, so that concurrent requests don't each fetch them
+  sync_lock: Mutex<()>,
+}
+
+impl SigningHistory {
+  pub fn new() -> Self {
+    SigningHistory {
+      blocks: RwLock::new(VecDeque::with_capacity(SIGNING_HISTORY_BLOCKS)),
+      validator_set: RwLock::new(None),
+      sync_lock: Mutex::new(()),
+    }
+  }
+
+  pub fn latest_height(&self) -> Option<u64> {
+    self.blocks.read().unwrap().back().map(|block| block.height)
+  }
+
+  /// The most recent `count` blocks, oldest first
+  pub fn recent(&self, count: usize) -> Vec<Arc<BlockSigning>> {
+    let blocks = self.blocks.read().unwrap();
+    blocks.iter().skip(blocks.len().saturating_sub(count)).cloned().collect()
+  }
+
+  /// Appends newly committed blocks, in height order, dropping the oldest beyond `SIGNING_HISTORY_BLOCKS`
+  /// Blocks that don't follow on from the latest one (eg: after the node was unreachable for a while) replace the history
+  pub fn extend(&self, new_blocks: Vec<BlockSigning>) {
+    let mut blocks = self.blocks.write().unwrap();
+    for block in new_blocks {
+      if blocks.back().is_some_and(|latest| latest.height + 1 != block.height) {
+        blocks.clear();
+      }
+      if blocks.len() == SIGNING_HISTORY_BLOCKS {
+        blocks.pop_front();
+      }
+      blocks.push_back(Arc::new(block));
+    }
+  }
+
+  pub fn validator_set(&self, validators_hash: &Hash) -> Option<Arc<Vec<String>>> {
+    match &*self.validator_set.read().unwrap() {
+      Some((hash, validator_set)) if hash == validators_hash => Some(Arc::clone(validator_set)),
+      _ => None,
+    }
+  }
+
+  pub fn set_validator_set(&self, validators_hash: Hash, validator_set: Arc<Vec<String>>) {
+    *self.validator_set.write().unwrap() = Some((validators_hash, validator_set));
+  }
+
+  /// Waits for any recording of new blocks in progress; the latest height should be checked again once held
+  pub async fn lock_sync(&self) -> MutexGuard<'_, ()> {
+    self.sync_lock.lock().await
+  }
+
+  pub fn is_syncing(&self) -> bool {
+    self.sync_lock.try_lock().is_err()
+  }
+}
+
+impl Default for SigningHistory {
+  fn default() -> Self {
+    SigningHistory::new()
+  }
+}
@@ -1,15 +1,22 @@
 use axum::{
+  body::{to_bytes, Body},
+  extract::Request,
   http::{StatusCode, header},
-  response::{IntoResponse, Json},
+  middleware::Next,
+  response::{IntoResponse, Json, Response},
 };
 use serde::Serialize;
 use serde_json::json;
-use namada_sdk::error::Error;
-use tendermint_rpc::Error as TendermintError;
+use namada_sdk::error::{Error, QueryError, TxError};
+use tendermint_rpc::{error::ErrorDetail, response_error::Code, Error as TendermintError};
 
+/// Extractor rejections (eg: a non-numeric height in the path) are short plain text messages
+const MAX_REJECTION_BODY: usize = 64 * 1024;
 
-// TODO: Make sure this error structure matches the Cosmos API error json
-/// Error json returned to client when an Api call fails due to e.g. a server side data query error
+
+/// Error returned to the client when an Api call fails, eg: due to a bad parameter or a failed query
+/// Rendered like the errors of the Cosmos-sdk's grpc-gateway: `{code, message, details}`, where `code` is the gRPC
+/// status code that corresponds to the http status
 #[derive(Debug)]
 pub struct ApiError {
   pub error: String,
@@ -18,17 +25,69 @@ pub struct ApiError {
   pub details: Vec<ErrDetails>,
 }
 
+/// gRPC status codes, as used in Cosmos error bodies
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GrpcCode {
+  Unknown = 2,
+  InvalidArgument = 3,
+  DeadlineExceeded = 4,
+  NotFound = 5,
+  PermissionDenied = 7,
+  ResourceExhausted = 8,
+  Unimplemented = 12,
+  Internal = 13,
+  Unavailable = 14,
+}
+
+impl GrpcCode {
+  /// The gRPC code for an http status, following grpc-gateway's mapping
+  pub fn from_status(status: StatusCode) -> Self {
+    match status {
+      StatusCode::BAD_REQUEST => GrpcCode::InvalidArgument,
+      StatusCode::NOT_FOUND => GrpcCode::NotFound,
+      StatusCode::FORBIDDEN => GrpcCode::PermissionDenied,
+      StatusCode::TOO_MANY_REQUESTS => GrpcCode::ResourceExhausted,
+      StatusCode::NOT_IMPLEMENTED => GrpcCode::Unimplemented,
+      StatusCode::SERVICE_UNAVAILABLE => GrpcCode::Unavailable,
+      StatusCode::GATEWAY_TIMEOUT => GrpcCode::DeadlineExceeded,
+      StatusCode::INTERNAL_SERVER_ERROR => GrpcCode::Internal,
+      status if status.is_client_error() => GrpcCode::InvalidArgument,
+      _ => GrpcCode::Unknown,
+    }
+  }
+}
+
+impl ApiError {
+  /// The Cosmos error message: the error, followed by the extra context in `message` if there is any
+  pub fn cosmos_message(&self) -> String {
+    match &self.message {
+      Some(message) if !message.is_empty() => format!("{}: {}", self.error, message),
+      _ => self.error.clone(),
+    }
+  }
+
+  /// An error for a failed query whose cause is only known from its message
+  /// Only a last resort, for errors that have no variants to tell them apart
+  pub fn from_query_message(error: String) -> Self {
+    ApiError {
+      code: status_from_message(&error, StatusCode::INTERNAL_SERVER_ERROR),
+      error,
+      message: None,
+      details: Vec::new(),
+    }
+  }
+}
+
 impl IntoResponse for ApiError {
-  
+
   fn into_response(self) -> axum::response::Response {
 
     (
       self.code,
       [(header::CONTENT_TYPE, "application/json")],
       Json(json!({
-        "error": self.error,
-        "code": self.code.as_u16(),
-        "message": self.message,
+        "code": GrpcCode::from_status(self.code) as u16,
+        "message": self.cosmos_message(),
         "details": self.details,
       }))
     ).into_response()
@@ -36,31 +95,62 @@ impl IntoResponse for ApiError {
 }
 
 // Conversion from Namada sdk error::Error
-// TODO: more informative errors, ie: fill other fields instead of blank/generic strings
 impl From<Error> for ApiError {
   fn from(err: Error) -> Self {
+    let code = match &err {
+      Error::Query(QueryError::NoSuchKey(_)) => StatusCode::NOT_FOUND,
+      // values in storage that fail to (de)serialize are a fault on our side, whatever their message says
+      Error::Query(QueryError::Decoding(_)) | Error::Query(QueryError::Encoding(_)) | Error::EncodingError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+      // the sdk stringifies the rpc client's error: either the node rejected the request (eg: a height it doesn't
+      // have), or no node could be reached (the rpc pool has already tried every node)
+      Error::Query(QueryError::NoResponse(message)) => status_from_message(message, StatusCode::SERVICE_UNAVAILABLE),
+      Error::Tx(TxError::InvalidValidatorAddress(_)) => StatusCode::NOT_FOUND,
+      // the tx errors raised by queries are checks of the arguments, eg: an amount or a bond that doesn't exist
+      Error::Tx(_) => StatusCode::BAD_REQUEST,
+      Error::Query(QueryError::General(message)) | Error::Other(message) => status_from_message(message, StatusCode::INTERNAL_SERVER_ERROR),
+      _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
     ApiError {
       error: err.to_string(),
-      code: StatusCode::INTERNAL_SERVER_ERROR,
-      message: Some("An error occurred".to_string()),
-      details: vec![ErrDetails {
-          type_url: "".to_string(),
-          value: "".to_string(),
-      }],
+      code,
+      message: None,
+      details: Vec::new(),
     }
   }
 }
 
 impl From<TendermintError> for ApiError {
   fn from(err: TendermintError) -> Self {
+    let code = match err.detail() {
+      // the node responded, so the request itself was the problem
+      ErrorDetail::Response(detail) => match detail.source.code() {
+        Code::ParseError | Code::InvalidRequest | Code::InvalidParams => StatusCode::BAD_REQUEST,
+        Code::MethodNotFound => StatusCode::NOT_IMPLEMENTED,
+        // CometBFT reports a height it doesn't have as an internal error, so only its message tells them apart
+        _ => status_from_message(&detail.source.to_string(), StatusCode::INTERNAL_SERVER_ERROR),
+      },
+      // anything else means no node could be reached (the rpc pool has already tried every node)
+      _ => StatusCode::SERVICE_UNAVAILABLE,
+    };
+
+    ApiError {
+      error: err.to_string(),
+      code,
+      message: None,
+      details: Vec::new(),
+    }
+  }
+}
+
+#[cfg(feature = "indexer")]
+impl From<rusqlite::Error> for ApiError {
+  fn from(err: rusqlite::Error) -> Self {
     ApiError {
       error: err.to_string(),
       code: StatusCode::INTERNAL_SERVER_ERROR,
-      message: Some("An error occurred".to_string()),
-      details: vec![ErrDetails {
-          type_url: "".to_string(),
-          value: "".to_string(),
-      }],
+      message: Some("An error occurred reading the tx index".to_string()),
+      details: Vec::new(),
     }
   }
 }
@@ -71,19 +161,55 @@ impl Default for ApiError {
       error: "api error".to_string(),
       code: StatusCode::INTERNAL_SERVER_ERROR,
       message: Some("An unknown error occurred".to_string()),
-      details: vec![ErrDetails {
-          type_url: "".to_string(),
-          value: "".to_string(),
-      }],
+      details: Vec::new(),
     }
   }
 }
 
 
+/// A Cosmos error detail, ie: a protobuf `Any`
 #[derive(Debug, Serialize)]
 pub struct ErrDetails {
+  #[serde(rename = "@type")]
   pub type_url: String,
   pub value: String,
 }
 
-// TODO: add a type for when the Cosmos rest endpoint is invalid, eg: 'not implemented'
\ No newline at end of file
+/// Guesses the http status of a failed query from its error message, for errors that only come as a message
+/// The patterns are the specific messages of the node and the sdk, falling back to `default` for anything else
+fn status_from_message(error: &str, default: StatusCode) -> StatusCode {
+  let error = error.to_lowercase();
+  let mentions = |patterns: &[&str]| patterns.iter().any(|pattern| error.contains(pattern));
+
+  if mentions(&["must be less than or equal to the current blockchain height", "is not available, lowest height", "invalid height"]) {
+    StatusCode::BAD_REQUEST
+  } else if mentions(&["not found", "does not exist", "doesn't exist"]) {
+    StatusCode::NOT_FOUND
+  } else if mentions(&["connection refused", "connection reset", "timed out", "error trying to connect", "dns error"]) {
+    StatusCode::SERVICE_UNAVAILABLE
+  } else {
+    default
+  }
+}
+
+/// Middleware turning axum's plain text rejections (eg: a path or query param that doesn't parse) into Cosmos error bodies
+pub async fn cosmos_rejections(request: Request, next: Next) -> Response {
+  let response = next.run(request).await;
+  let is_plain_text = response.headers().get(header::CONTENT_TYPE)
+    .and_then(|content_type| content_type.to_str().ok())
+    .is_some_and(|content_type| content_type.starts_with("text/plain"));
+  if !response.status().is_client_error() || !is_plain_text {
+    return response;
+  }
+
+  let (parts, body) = response.into_parts();
+  match to_bytes(body, MAX_REJECTION_BODY).await {
+    Ok(bytes) => ApiError {
+      error: String::from_utf8_lossy(&bytes).to_string(),
+      code: parts.status,
+      message: None,
+      details: Vec::new(),
+    }.into_response(),
+    Err(_) => Response::from_parts(parts, Body::empty()),
+  }
+}
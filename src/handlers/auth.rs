@@ -0,0 +1,96 @@
+use axum::{
+  extract::{Path, State},
+  http::StatusCode,
+  Json,
+};
+use std::sync::Arc;
+use namada_sdk::{
+  rpc,
+  types::address::Address,
+};
+use namada_parameters::storage;
+
+use crate::app::app_state::AppState;
+use crate::error::api_error::ApiError;
+use crate::handlers::shared::resolve_address;
+use crate::model::{
+  auth::{AccountPubKey, AccountResponse, AuthParamsInfo, AuthParamsResponse, BaseAccount, MultisigPubKey},
+  staking::ConsensusKeyInfo,
+};
+
+
+pub async fn account_handler(Path(address): Path<String>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<AccountResponse>, ApiError> {
+
+  let address = resolve_address(&app_state, &address).await?;
+
+  let pub_key = match address {
+    // an implicit account exists as soon as it has an address; it only has a public key once it has been revealed
+    Address::Implicit(_) => {
+      rpc::get_public_key_at(app_state.get_client(), &address, 0).await?
+        .map(|pk| AccountPubKey::Single(ConsensusKeyInfo::from(&pk)))
+    }
+    _ => {
+      let account = match rpc::get_account_info(app_state.get_client(), &address).await? {
+        Some(account) => account,
+        None => return Err(ApiError {
+          error: format!("account {} not found", address),
+          code: StatusCode::NOT_FOUND,
+          message: None,
+          details: Vec::new(),
+        }),
+      };
+
+      let mut public_keys: Vec<(u8, ConsensusKeyInfo)> = account.public_keys_map.idx_to_pk.iter()
+        .map(|(idx, pk)| (*idx, ConsensusKeyInfo::from(pk)))
+        .collect();
+      public_keys.sort_by_key(|(idx, _)| *idx);
+      let mut public_keys: Vec<ConsensusKeyInfo> = public_keys.into_iter().map(|(_, pk)| pk).collect();
+
+      match (public_keys.len(), account.threshold) {
+        // eg: internal addresses or established accounts without keys
+        (0, _) => None,
+        (1, 1) => public_keys.pop().map(AccountPubKey::Single),
+        (_, threshold) => Some(AccountPubKey::Multisig(MultisigPubKey {
+          at_type: "/cosmos.crypto.multisig.LegacyAminoPubKey".to_string(),
+          threshold: threshold as u32,
+          public_keys,
+        })),
+      }
+    }
+  };
+
+  let response = AccountResponse {
+    account: BaseAccount {
+      at_type: "/cosmos.auth.v1beta1.BaseAccount".to_string(),
+      address,
+      pub_key,
+      // Namada has no account numbers, and replay protection is done by tx hash rather than a sequence number
+      account_number: "0".to_string(),
+      sequence: "0".to_string(),
+    },
+  };
+
+  Ok(Json(response))
+}
+
+pub async fn auth_params_handler(State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<AuthParamsResponse>, ApiError> {
+
+  let max_signatures: u8 = rpc::query_storage_value(app_state.get_client(), &storage::get_max_signatures_per_transaction_key()).await?;
+  let max_tx_bytes: u32 = rpc::query_storage_value(app_state.get_client(), &storage::get_max_tx_bytes_key()).await?;
+
+  let response = AuthParamsResponse {
+    params: AuthParamsInfo {
+      // memos are a section of the tx, so they are only limited by the max tx size
+      max_memo_characters: max_tx_bytes.to_string(),
+      tx_sig_limit: max_signatures.to_string(),
+      // TODO: Namada gas is metered differently; these are the Cosmos sdk defaults
+      tx_size_cost_per_byte: "10".to_string(),
+      sig_verify_cost_ed25519: "590".to_string(),
+      sig_verify_cost_secp256k1: "1000".to_string(),
+    },
+  };
+
+  Ok(Json(response))
+}
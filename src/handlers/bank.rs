@@ -1,43 +1,65 @@
 use axum::{
-  extract::State,
+  extract::{Path, State},
+  http::HeaderMap,
   Json,
 };
 use std::sync::Arc;
+use namada_sdk::{rpc, types::token::{self, Amount}};
 
 use crate::app::app_state::AppState;
 use crate::error::api_error::ApiError;
+use crate::handlers::shared::{query_balances_at, query_storage_value_at, resolve_address, QueryHeight};
 use crate::model::{
-  bank::{SupplyResponse, SupplyDenomResponse},
+  bank::{BalancesResponse, SupplyResponse, SupplyDenomResponse},
   shared::{DenomAmount, PaginationInfo},
 };
 
 
-pub async fn supply_handler(State(_app_state): State<Arc<AppState>>) 
-  -> Result<Json<SupplyResponse>, ApiError> {
+pub async fn supply_handler(height: QueryHeight, State(app_state): State<Arc<AppState>>) 
+  -> Result<(HeaderMap, Json<SupplyResponse>), ApiError> {
   
   let response = SupplyResponse {
-    supply: vec![DenomAmount {
-      denom: "tnam1234".to_string(),
-      amount: "1000".to_string(),
-    }],
+    supply: vec![get_nam_supply(&app_state, height).await?],
     pagination: PaginationInfo {
       next_key: None,
       total: Some("1".to_string()),
     }
 };
 
-  Ok(Json(response))
+  Ok((height.headers(), Json(response)))
 }
 
-pub async fn supply_denom_handler(State(_app_state): State<Arc<AppState>>) 
-  -> Result<Json<SupplyDenomResponse>, ApiError> {
+pub async fn supply_denom_handler(height: QueryHeight, State(app_state): State<Arc<AppState>>) 
+  -> Result<(HeaderMap, Json<SupplyDenomResponse>), ApiError> {
   
   let response = SupplyDenomResponse {
-    amount: DenomAmount {
-      denom: "tnam1234".to_string(),
-      amount: "1000".to_string(),
-    },
+    amount: get_nam_supply(&app_state, height).await?,
 };
 
-  Ok(Json(response))
-}
\ No newline at end of file
+  Ok((height.headers(), Json(response)))
+}
+
+pub async fn balances_handler(Path(address): Path<String>, height: QueryHeight, State(app_state): State<Arc<AppState>>) 
+  -> Result<(HeaderMap, Json<BalancesResponse>), ApiError> {
+
+  let owner = resolve_address(&app_state, &address).await?;
+  let balances = query_balances_at(&app_state, &owner, height).await?;
+
+  let response = BalancesResponse {
+    pagination: PaginationInfo {
+      next_key: None,
+      total: Some(balances.len().to_string()),
+    },
+    balances,
+  };
+
+  Ok((height.headers(), Json(response)))
+}
+
+/// Queries the amount of NAM minted as of the height
+async fn get_nam_supply(app_state: &AppState, height: QueryHeight) -> Result<DenomAmount, ApiError> {
+  let native_token = rpc::query_native_token(app_state.get_client()).await?;
+  let minted: Option<Amount> = query_storage_value_at(app_state, &token::minted_balance_key(&native_token), height).await?;
+
+  Ok(DenomAmount::nam_amount(minted.unwrap_or_default()))
+}
@@ -1,12 +1,15 @@
 use axum::{
-  extract::State,
+  extract::{Path, State},
+  http::{HeaderMap, StatusCode},
   Json,
 };
+use futures::future::try_join_all;
 use std::sync::Arc;
 use namada_sdk::{
   proof_of_stake::PosParams,
   rpc,
   types::{
+    address::{Address, InternalAddress},
     token::Amount,
     dec::Dec,
   },
@@ -14,22 +17,26 @@ use namada_sdk::{
 
 use crate::app::app_state::AppState;
 use crate::error::api_error::ApiError;
+use crate::handlers::shared::{query_balances_at, resolve_address, QueryHeight};
 use crate::model::{
   shared::DenomAmount,
-  distribution::{CommunityPoolResponse, DistibutionParamsInfo, DistributionParamsResponse},
+  distribution::{
+    CommunityPoolResponse, DelegationRewardsResponse, DelegatorRewardsResponse, DelegatorValidatorReward, DistibutionParamsInfo, DistributionParamsResponse,
+    ValidatorAccumulatedCommission, ValidatorCommissionResponse, ValidatorDistributionInfoResponse, ValidatorOutstandingRewards, ValidatorOutstandingRewardsResponse,
+  },
 };
 
 
-pub async fn community_pool_handler(State(_app_state): State<Arc<AppState>>) 
-  -> Result<Json<CommunityPoolResponse>, ApiError> {
+pub async fn community_pool_handler(height: QueryHeight, State(app_state): State<Arc<AppState>>) 
+  -> Result<(HeaderMap, Json<CommunityPoolResponse>), ApiError> {
   
-  // TODO: is there a pgf pool we can query the balance of?
-  let amount = Amount::from_u64(0);
-  let response = CommunityPoolResponse {
-    pool: vec![DenomAmount::nam_amount(amount)],
-  };
+  // the closest thing to the community pool is the PGF treasury
+  let pgf_address = Address::Internal(InternalAddress::Pgf);
+  let pool = query_balances_at(&app_state, &pgf_address, height).await?;
 
-  Ok(Json(response))
+  let response = CommunityPoolResponse { pool };
+
+  Ok((height.headers(), Json(response)))
 }
 
 pub async fn distribution_params_handler(State(app_state): State<Arc<AppState>>) 
@@ -54,4 +61,139 @@ pub async fn distribution_params_handler(State(app_state): State<Arc<AppState>>)
   };
 
   Ok(Json(response))
-}
\ No newline at end of file
+}
+
+pub async fn delegator_rewards_handler(Path(delegator): Path<String>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<DelegatorRewardsResponse>, ApiError> {
+
+  let delegator = resolve_address(&app_state, &delegator).await?;
+  let validators = rpc::get_delegators_delegation(app_state.get_client(), &delegator).await?;
+
+  let tasks = validators.into_iter().map(|validator| {
+    let source = Some(delegator.clone());
+    let client = app_state.get_client();
+    async move {
+      let reward = rpc::query_rewards(client, &source, &validator).await?;
+      Ok::<_, ApiError>((validator, reward))
+    }
+  });
+  let rewards: Vec<(Address, Amount)> = try_join_all(tasks).await?;
+
+  // rewards are only ever paid out in the native token
+  let mut total = Amount::zero();
+  let mut response = DelegatorRewardsResponse {
+    rewards: Vec::new(),
+    total: Vec::new(),
+  };
+  for (validator, reward) in rewards {
+    total += reward;
+    response.rewards.push(DelegatorValidatorReward {
+      validator_address: app_state.address_map.operator_address(&validator),
+      reward: vec![DenomAmount::nam_amount(reward)],
+    });
+  }
+  response.total.push(DenomAmount::nam_amount(total));
+
+  Ok(Json(response))
+}
+
+pub async fn delegation_rewards_handler(Path((delegator, validator)): Path<(String, String)>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<DelegationRewardsResponse>, ApiError> {
+
+  let delegator = resolve_address(&app_state, &delegator).await?;
+  let validator = resolve_address(&app_state, &validator).await?;
+  let reward = rpc::query_rewards(app_state.get_client(), &Some(delegator), &validator).await?;
+
+  let response = DelegationRewardsResponse {
+    rewards: vec![DenomAmount::nam_amount(reward)],
+  };
+
+  Ok(Json(response))
+}
+
+pub async fn validator_commission_handler(Path(validator): Path<String>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<ValidatorCommissionResponse>, ApiError> {
+
+  let validator = resolve_address(&app_state, &validator).await?;
+  let (_, commission) = get_validator_rewards(&app_state, &validator).await?;
+
+  let response = ValidatorCommissionResponse {
+    commission: ValidatorAccumulatedCommission {
+      commission: vec![DenomAmount::nam_amount(commission)],
+      estimated: true,
+    },
+  };
+
+  Ok(Json(response))
+}
+
+pub async fn validator_outstanding_rewards_handler(Path(validator): Path<String>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<ValidatorOutstandingRewardsResponse>, ApiError> {
+
+  let validator = resolve_address(&app_state, &validator).await?;
+  let (self_bond_rewards, commission) = get_validator_rewards(&app_state, &validator).await?;
+
+  let response = ValidatorOutstandingRewardsResponse {
+    rewards: ValidatorOutstandingRewards {
+      rewards: vec![DenomAmount::nam_amount(self_bond_rewards + commission)],
+    },
+  };
+
+  Ok(Json(response))
+}
+
+pub async fn validator_distribution_info_handler(Path(validator): Path<String>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<ValidatorDistributionInfoResponse>, ApiError> {
+
+  let validator = resolve_address(&app_state, &validator).await?;
+  let (self_bond_rewards, commission) = get_validator_rewards(&app_state, &validator).await?;
+
+  let response = ValidatorDistributionInfoResponse {
+    operator_address: app_state.address_map.operator_address(&validator),
+    self_bond_rewards: vec![DenomAmount::nam_amount(self_bond_rewards)],
+    commission: vec![DenomAmount::nam_amount(commission)],
+    estimated: true,
+  };
+
+  Ok(Json(response))
+}
+
+/// Queries the rewards a validator can claim, split into (self-bond rewards, commission)
+/// Namada credits commission to the same rewards counter as the validator's self-bond rewards, so the split is estimated
+/// from the current commission rate and the share of the validator's stake that is delegated.
+/// Delegators' unclaimed rewards aren't tracked per validator, so they aren't part of the outstanding rewards
+async fn get_validator_rewards(app_state: &AppState, validator: &Address) -> Result<(Amount, Amount), ApiError> {
+  let current_epoch = rpc::query_epoch(app_state.get_client()).await?;
+  let rewards = rpc::query_rewards(app_state.get_client(), &None, validator).await?;
+  let self_bond = rpc::query_bond(app_state.get_client(), validator, validator, Some(current_epoch)).await?;
+  let total_stake = rpc::get_validator_stake(app_state.get_client(), current_epoch, validator).await?;
+  let commission_rate = match rpc::query_commission_rate(app_state.get_client(), validator, Some(current_epoch)).await? {
+    Some(commission_pair) => commission_pair.commission_rate,
+    None => Dec::zero(),
+  };
+
+  // per unit of reward, the validator earns `self_bond + rate * delegated`, of which `rate * delegated` is commission
+  let delegated = amount_to_dec(total_stake.checked_sub(self_bond).unwrap_or_default())?;
+  let commission_weight = commission_rate * delegated;
+  let validator_weight = amount_to_dec(self_bond)? + commission_weight;
+  let commission_share = if validator_weight.is_zero() {
+    Dec::zero()
+  } else {
+    commission_weight / validator_weight
+  };
+
+  let commission = rewards.mul_floor(commission_share);
+  let self_bond_rewards = rewards.checked_sub(commission).unwrap_or_default();
+
+  Ok((self_bond_rewards, commission))
+}
+
+/// Stake as a decimal (in base units), to weigh it against other stake
+fn amount_to_dec(amount: Amount) -> Result<Dec, ApiError> {
+  Dec::try_from(amount).map_err(|err| ApiError {
+    error: "could not convert stake to a decimal".to_string(),
+    code: StatusCode::INTERNAL_SERVER_ERROR,
+    message: Some(err.to_string()),
+    details: Vec::new(),
+  })
+}
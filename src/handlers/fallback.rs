@@ -0,0 +1,86 @@
+use axum::{
+  extract::State,
+  http::{StatusCode, Uri},
+  response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use tracing::info;
+
+use crate::app::app_state::AppState;
+use crate::error::api_error::ApiError;
+
+/// Path prefixes of the Cosmos-sdk REST api (including the IBC and CosmWasm modules)
+const COSMOS_PREFIXES: [&str; 3] = ["/cosmos/", "/ibc/", "/cosmwasm/"];
+
+
+/// Answers requests that no route matched: Cosmos endpoints that aren't implemented yet get a gRPC Unimplemented
+/// error (and are logged and counted, to see which ones the explorer uses), anything else is a plain 404
+pub async fn fallback_handler(uri: Uri, State(app_state): State<Arc<AppState>>) -> Response {
+  let path = uri.path();
+  if !COSMOS_PREFIXES.iter().any(|prefix| path.starts_with(prefix)) {
+    return StatusCode::NOT_FOUND.into_response();
+  }
+
+  let route = route_pattern(path);
+  info!("unimplemented cosmos endpoint requested: {}", path);
+  app_state.metrics.record_unimplemented(&route);
+
+  ApiError {
+    error: "Not Implemented".to_string(),
+    code: StatusCode::NOT_IMPLEMENTED,
+    message: Some(format!("{} is not supported by this api yet", path)),
+    details: Vec::new(),
+  }.into_response()
+}
+
+/// Replaces the path segments that look like parameters (addresses, heights, ids, hashes) with ':param', so that
+/// requests for the same endpoint are counted together, eg: /cosmos/staking/v1beta1/delegations/tnam1q... ->
+/// /cosmos/staking/v1beta1/delegations/:param
+fn route_pattern(path: &str) -> String {
+  path.split('/')
+    .map(|segment| if is_parameter(segment) { ":param" } else { segment })
+    .collect::<Vec<&str>>()
+    .join("/")
+}
+
+fn is_parameter(segment: &str) -> bool {
+  if segment.len() > 24 {
+    return true;
+  }
+
+  // module versions, eg: v1, v1beta1, v2alpha1, are part of the route
+  let is_version = segment.strip_prefix('v').is_some_and(|rest| {
+    let rest = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+    let rest = rest.strip_prefix("beta").or_else(|| rest.strip_prefix("alpha")).unwrap_or(rest);
+    rest.chars().all(|c| c.is_ascii_digit())
+  }) && segment.len() > 1;
+
+  !is_version && segment.contains(|c: char| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn addresses_and_heights_are_parameters() {
+    assert_eq!(
+      route_pattern("/cosmos/staking/v1beta1/delegations/tnam1qxgzrwqn9qny9fzd7xnlrdkf7hhj9ecyx5mv3sgw"),
+      "/cosmos/staking/v1beta1/delegations/:param",
+    );
+    assert_eq!(route_pattern("/cosmos/base/tendermint/v1beta1/blocks/123"), "/cosmos/base/tendermint/v1beta1/blocks/:param");
+    assert_eq!(route_pattern("/ibc/core/channel/v1/channels/channel-0/ports/transfer"), "/ibc/core/channel/v1/channels/:param/ports/transfer");
+  }
+
+  #[test]
+  fn versions_and_names_are_kept() {
+    for path in ["/cosmos/gov/v1/params/voting", "/cosmos/feegrant/v1beta1/allowances", "/cosmos/upgrade/v2alpha1/current_plan"] {
+      assert_eq!(route_pattern(path), path);
+    }
+  }
+
+  #[test]
+  fn long_segments_are_parameters() {
+    assert_eq!(route_pattern("/cosmos/tx/v1beta1/txs/ABCDEFABCDEFABCDEFABCDEFABCDEF"), "/cosmos/tx/v1beta1/txs/:param");
+  }
+}
@@ -1,9 +1,8 @@
 use axum::{
-  extract::{Path, Query, State}, http::StatusCode, Json
+  extract::{Path, Query, State}, http::{HeaderMap, StatusCode}, Json
 };
-use futures::future::try_join_all;
+use futures::{stream, StreamExt, TryStreamExt};
 use tendermint::v0_34::abci::response;
-use tendermint_rpc::HttpClient;
 use std::{
   sync::Arc,
   str::FromStr,
@@ -19,32 +18,28 @@ use namada_sdk::{
   },
   // core::ledger::parameters::{storage, EpochDuration},
 };
-use namada_sdk::governance::{storage::keys as governance_storage, utils::{ProposalStatus, ProposalResult, TallyResult}};
+use namada_sdk::governance::{storage::{keys as governance_storage, proposal::StorageProposal}, utils::{ProposalStatus, ProposalResult, TallyResult}};
 use namada_sdk::proof_of_stake::Epoch;
 use namada_sdk::types::parameters::EpochDuration;
 use namada_parameters::storage;
 
-use crate::app::app_state::AppState;
+use crate::app::{app_state::AppState, chain_follower::SnapshotFreshness};
 use crate::error::api_error::ApiError;
+use crate::handlers::shared::{freshness_headers, query_epoch_at, query_storage_value_at, QueryHeight};
 use crate::model::{
-  gov::{ParamsGovResponse, TallyResponse, FinalTallyInfo, ProposalInfo, ProposalsResponse, IndividualProposalResponse, ProposalItem, ProposalQueryParams, CosmosProposalStatus},
-  shared::{DEFAULT_TIMESTAMP, DenomAmount, SuffixedDur},
+  gov::{ParamsGovResponse, VotingParams, DepositParams, TallyParams, TallyResponse, FinalTallyInfo, ProposalsResponse, IndividualProposalResponse, ProposalItem, ProposalData, ProposalQueryParams, CosmosProposalStatus},
+  shared::{DenomAmount, SuffixedDur, PaginationInfo, PaginationQueryParams},
 };
 
+/// Max number of proposals queried from the node at the same time
+pub(crate) const PROPOSAL_QUERY_CONCURRENCY: usize = 8;
+
 
 pub async fn params_voting_handler(State(app_state): State<Arc<AppState>>) 
   -> Result<Json<ParamsGovResponse>, ApiError> {
   
-  let gov_params = rpc::query_governance_parameters(app_state.get_client()).await;
-  let epoch_dur: EpochDuration = rpc::query_storage_value(app_state.get_client(), &storage::get_epoch_duration_storage_key()).await?;
-  
   let mut response = ParamsGovResponse::default();
-
-  // TODO: Namada voting period can be anything within an allowed range
-  // Cosmos has fixed voting period so we're just returning Namada's min_duration value here
-  // perhaps it could be handled differently?
-  let voting_period = Duration::from(epoch_dur.min_duration) * (gov_params.min_proposal_voting_period as u32);
-  response.voting_params.voting_period = SuffixedDur(voting_period);
+  response.voting_params = get_voting_params(&app_state).await?;
 
   Ok(Json(response))
 }
@@ -52,12 +47,8 @@ pub async fn params_voting_handler(State(app_state): State<Arc<AppState>>)
 pub async fn params_deposit_handler(State(app_state): State<Arc<AppState>>) 
   -> Result<Json<ParamsGovResponse>, ApiError> {
   
-  let gov_params = rpc::query_governance_parameters(app_state.get_client()).await;
-
-  // TODO: can't find an equivalent to max_deposit_period
   let mut response = ParamsGovResponse::default();
-  let deposit = DenomAmount::nam_amount(gov_params.min_proposal_fund);
-  response.deposit_params.min_deposit.push(deposit);
+  response.deposit_params = get_deposit_params(&app_state).await;
 
   Ok(Json(response))
 }
@@ -65,159 +56,331 @@ pub async fn params_deposit_handler(State(app_state): State<Arc<AppState>>)
 pub async fn params_tallying_handler(State(_app_state): State<Arc<AppState>>) 
   -> Result<Json<ParamsGovResponse>, ApiError> {
   
-  // TODO: do these params have equivalents in Namada?
-  // looks like quorum might be dependant on proposal type (see enum 'TallyType')
-  // hardcoding for now...
   let mut response = ParamsGovResponse::default();
-  
-  response.tally_params.quorum = Dec::zero();
-  response.tally_params.threshold = Dec::from_str("0.67").unwrap();
-  response.tally_params.veto_threshold = Dec::one();
+  response.tally_params = get_tally_params();
 
   Ok(Json(response))
 }
 
-pub async fn all_proposals_handler(query: Query<ProposalQueryParams>, State(app_state): State<Arc<AppState>>) 
-  -> Result<Json<ProposalsResponse>, ApiError> {
+pub(crate) async fn get_voting_params(app_state: &AppState) -> Result<VotingParams, ApiError> {
+  let gov_params = rpc::query_governance_parameters(app_state.get_client()).await;
+  let epoch_dur: EpochDuration = rpc::query_storage_value(app_state.get_client(), &storage::get_epoch_duration_storage_key()).await?;
 
-  let last_proposal_id_key = governance_storage::get_counter_key();
-  let last_proposal_id: u64 = rpc::query_storage_value(app_state.get_client(), &last_proposal_id_key).await?;
+  // TODO: Namada voting period can be anything within an allowed range
+  // Cosmos has fixed voting period so we're just returning Namada's min_duration value here
+  // perhaps it could be handled differently?
+  let voting_period = Duration::from(epoch_dur.min_duration) * (gov_params.min_proposal_voting_period as u32);
 
-  // get deposit amount
+  Ok(VotingParams { voting_period: SuffixedDur(voting_period) })
+}
+
+pub(crate) async fn get_deposit_params(app_state: &AppState) -> DepositParams {
   let gov_params = rpc::query_governance_parameters(app_state.get_client()).await;
-  let deposit = DenomAmount::nam_amount(gov_params.min_proposal_fund);
 
-  let mut tasks = Vec::new();
+  // TODO: can't find an equivalent to max_deposit_period
+  DepositParams {
+    min_deposit: vec![DenomAmount::nam_amount(gov_params.min_proposal_fund)],
+    max_deposit_period: "0s".to_string(),
+  }
+}
 
-  // Spawn a task for each entry in consensus set
-  for id in 0..last_proposal_id {
-      let task = fetch_proposal_info(app_state.get_client(), id, deposit.clone());
-      tasks.push(task);
+pub(crate) fn get_tally_params() -> TallyParams {
+  // TODO: do these params have equivalents in Namada?
+  // looks like quorum might be dependant on proposal type (see enum 'TallyType')
+  // hardcoding for now...
+  TallyParams {
+    quorum: Dec::zero(),
+    threshold: Dec::from_str("0.67").unwrap(),
+    veto_threshold: Dec::one(),
   }
+}
 
-  // Collect results of all tasks concurrently
-  let all_proposals: Vec<Option<ProposalItem>> = try_join_all(tasks).await?;
-  // Remove any 'None' values that might have been returned when querying a proposal id
-  let valid_proposals: Vec<ProposalItem> = all_proposals.into_iter()
-    .filter_map(|opt_proposal| opt_proposal)
-    .collect();
+pub async fn all_proposals_handler(query: Query<ProposalQueryParams>, height: QueryHeight, State(app_state): State<Arc<AppState>>) 
+  -> Result<(HeaderMap, Json<ProposalsResponse>), ApiError> {
+
+  let (proposals, pagination, freshness) = get_all_proposals(app_state, query.proposal_status, &query.pagination, height).await?;
 
   let response = ProposalsResponse {
-    proposals: valid_proposals,
-    pagination: None,
+    proposals: proposals.into_iter().map(ProposalItem::from).collect(),
+    pagination: Some(pagination),
   };
 
-  Ok(Json(response))
-}
+  let mut headers = freshness_headers(freshness);
+  headers.extend(height.headers());
 
-async fn fetch_proposal_info(client: &HttpClient, id: u64, deposit: DenomAmount) -> Result<Option<ProposalItem>, ApiError> {
-  if let Some(proposal) = rpc::query_proposal_by_id(client, id).await? {
-    let mut proposal_item = ProposalItem::from(proposal);
-    proposal_item.total_deposit = vec![deposit];
-    return Ok(Some(proposal_item));
-  }
-  
-  Ok(None)
+  Ok((headers, Json(response)))
 }
 
-pub async fn single_proposal_handler(Path(id): Path<u64>, State(app_state): State<Arc<AppState>>) 
-  -> Result<Json<IndividualProposalResponse>, ApiError> {
+pub async fn single_proposal_handler(Path(id): Path<u64>, height: QueryHeight, State(app_state): State<Arc<AppState>>) 
+  -> Result<(HeaderMap, Json<IndividualProposalResponse>), ApiError> {
 
-  let current_epoch = rpc::query_epoch(app_state.get_client()).await?;
-  let proposal = get_proposal(app_state, id, current_epoch, None).await?;
-  let response = IndividualProposalResponse { proposal };
+  let proposal = get_proposal(app_state, id, height, None).await?.ok_or_else(|| proposal_not_found(id))?;
+  let response = IndividualProposalResponse { proposal: Some(ProposalItem::from(proposal)) };
 
-  Ok(Json(response))
+  Ok((height.headers(), Json(response)))
   }
 
-pub async fn proposal_tally_handler(Path(id): Path<u64>, State(app_state): State<Arc<AppState>>) 
-  -> Result<Json<TallyResponse>, ApiError> {
+pub async fn proposal_tally_handler(Path(id): Path<u64>, height: QueryHeight, State(app_state): State<Arc<AppState>>) 
+  -> Result<(HeaderMap, Json<TallyResponse>), ApiError> {
   
+  let tally = get_proposal_tally(app_state, id, height).await?;
+  let response = TallyResponse { tally };
+
+  Ok((height.headers(), Json(response)))
+}
+
+/// Queries the tally of a proposal; returns an error if the proposal has no result in storage
+/// Live tallies are computed from the latest votes, so at a past height only final tallies are available
+pub(crate) async fn get_proposal_tally(app_state: Arc<AppState>, id: u64, height: QueryHeight) -> Result<FinalTallyInfo, ApiError> {
   let mut final_tally = FinalTallyInfo::default();
-  let proposal_result = get_proposal_result(app_state, id).await?;
+  let current_epoch = query_epoch_at(&app_state, height).await?;
+  if height.0.is_some() {
+    let proposal = get_proposal(app_state.clone(), id, height, None).await?.ok_or_else(|| proposal_not_found(id))?;
+    if matches!(proposal.status, CosmosProposalStatus::PROPOSAL_STATUS_VOTING_PERIOD) {
+      return Err(ApiError {
+        error: format!("proposal {} was in its voting period at height {}", id, height.0.unwrap_or_default()),
+        code: StatusCode::NOT_IMPLEMENTED,
+        message: Some("live tallies can only be queried at the latest height".to_string()),
+        details: Vec::new(),
+      });
+    }
+  }
+  let proposal_result = get_proposal_result(app_state, id, current_epoch, height).await?;
   //
   if let Some(proposal_result) = proposal_result {
     final_tally.yes = proposal_result.total_yay_power.to_string_native();
     final_tally.no = proposal_result.total_nay_power.to_string_native();
-    let response = TallyResponse {tally: final_tally };
-    return Ok(Json(response))
+    return Ok(final_tally)
   }
 
   return Err(ApiError {
-    error: "proposal result not found".to_string(),
-    code: StatusCode::INTERNAL_SERVER_ERROR,
+    error: format!("proposal {} doesn't exist or has no result", id),
+    code: StatusCode::NOT_FOUND,
     message: None,
     details: Vec::new(),
   })
+}
+
+/// Queries the requested page of proposals whose status matches the (optional) requested status
+/// If the chain follower has built a snapshot, the page is served from it (along with its freshness)
+/// Otherwise proposals are served from the cache where possible, and the status filter and pagination are applied
+/// before querying tallies, so that only the proposals on the returned page need their votes tallied
+/// At a past height, only the proposals submitted by then are listed, with their status as of that height's epoch
+pub(crate) async fn get_all_proposals(app_state: Arc<AppState>, requested_status: Option<u32>, pagination: &PaginationQueryParams, height: QueryHeight) 
+  -> Result<(Vec<ProposalData>, PaginationInfo, Option<SnapshotFreshness>), ApiError> {
+
+  // the snapshot only holds the latest state
+  if let (None, Some(snapshot)) = (height.0, app_state.current_snapshot()) {
+    let matching_proposals: Vec<ProposalData> = snapshot.proposals.iter()
+      .filter(|data| filter_proposal_by_status(requested_status, data.status))
+      .cloned()
+      .collect();
+    let (page, pagination_info) = pagination.paginate(matching_proposals);
+    return Ok((page, pagination_info, Some(snapshot.freshness())));
+  }
+
+  let current_epoch = query_epoch_at(&app_state, height).await?;
+  let proposals = get_storage_proposals(&app_state, height).await?;
+
+  // filter on the status we can tell from the voting epochs alone; only ended proposals need their
+  // (final, cached) result to tell whether they passed or were rejected, which is queried a few at a time
+  let matches_status: Vec<bool> = stream::iter(&proposals)
+    .map(|proposal| {
+      let app_state = app_state.clone();
+      async move {
+        let matches_status = match (proposal.get_status(current_epoch), requested_status) {
+          // no status specified; return all
+          (_, None) => true,
+          (ProposalStatus::Ended, Some(status)) if status >= u32::from(CosmosProposalStatus::PROPOSAL_STATUS_PASSED) => {
+            match get_proposal_result(app_state, proposal.id, current_epoch, height).await? {
+              Some(result) => filter_proposal_by_status(requested_status, map_status_namada_to_cosmos(ProposalStatus::Ended, result.result)),
+              None => false,
+            }
+          },
+          (ProposalStatus::Ended, Some(_)) => false,
+          // the tally result is only used for ended proposals
+          (proposal_status, Some(_)) => filter_proposal_by_status(requested_status, map_status_namada_to_cosmos(proposal_status, TallyResult::Rejected)),
+        };
+        Ok::<_, ApiError>(matches_status)
+      }
+    })
+    .buffered(PROPOSAL_QUERY_CONCURRENCY)
+    .try_collect()
+    .await?;
+  let matching_proposals: Vec<StorageProposal> = proposals.into_iter()
+    .zip(matches_status)
+    .filter_map(|(proposal, matches_status)| matches_status.then_some(proposal))
+    .collect();
 
-  // Ok(Json(response))
+  let (page, pagination_info) = pagination.paginate(matching_proposals);
+
+  // get deposit amount
+  let gov_params = rpc::query_governance_parameters(app_state.get_client()).await;
+  let deposit = DenomAmount::nam_amount(gov_params.min_proposal_fund);
+
+  let page_data: Vec<Option<ProposalData>> = stream::iter(page)
+    .map(|proposal| get_proposal_data(app_state.clone(), proposal, current_epoch, deposit.clone(), height))
+    .buffered(PROPOSAL_QUERY_CONCURRENCY)
+    .try_collect()
+    .await?;
+
+  Ok((page_data.into_iter().flatten().collect(), pagination_info, None))
+}
+
+/// Queries every proposal along with its status and tally, eg: for the chain follower's snapshot
+pub(crate) async fn get_all_proposal_data(app_state: Arc<AppState>, current_epoch: Epoch) -> Result<Vec<ProposalData>, ApiError> {
+  let proposals = get_storage_proposals(&app_state, QueryHeight::default()).await?;
+
+  let gov_params = rpc::query_governance_parameters(app_state.get_client()).await;
+  let deposit = DenomAmount::nam_amount(gov_params.min_proposal_fund);
+
+  let proposal_data: Vec<Option<ProposalData>> = stream::iter(proposals)
+    .map(|proposal| get_proposal_data(app_state.clone(), proposal, current_epoch, deposit.clone(), QueryHeight::default()))
+    .buffered(PROPOSAL_QUERY_CONCURRENCY)
+    .try_collect()
+    .await?;
+
+  Ok(proposal_data.into_iter().flatten().collect())
 }
 
-/// retrieves proposal info by id and formats it into a ProposalItem struct
-async fn get_proposal(app_state: Arc<AppState>, id: u64, current_epoch: Epoch, requested_status: Option<u32>) -> Result<Option<ProposalItem>, ApiError> {
-  match rpc::query_proposal_by_id(app_state.get_client(), id).await? {
-    // the rpc query may return None; also, if the status doesn't match the filter parmas we will return None
-    // neither of these cases will be considered errors, it just means nothing needs to be appended to the eventual Api response
+/// Queries every proposal submitted as of the height
+async fn get_storage_proposals(app_state: &AppState, height: QueryHeight) -> Result<Vec<StorageProposal>, ApiError> {
+  let last_proposal_id = get_proposal_count(app_state, height).await?;
+
+  // fetch any proposals we haven't seen yet, a few at a time rather than all at once
+  let proposals: Vec<Option<StorageProposal>> = stream::iter(0..last_proposal_id)
+    .map(|id| get_storage_proposal(app_state, id))
+    .buffered(PROPOSAL_QUERY_CONCURRENCY)
+    .try_collect()
+    .await?;
+
+  Ok(proposals.into_iter().flatten().collect())
+}
+
+/// The number of proposals submitted as of the height, ie: the id the next proposal will get
+async fn get_proposal_count(app_state: &AppState, height: QueryHeight) -> Result<u64, ApiError> {
+  let counter_key = governance_storage::get_counter_key();
+  Ok(query_storage_value_at(app_state, &counter_key, height).await?.unwrap_or_default())
+}
+
+pub(crate) fn proposal_not_found(id: u64) -> ApiError {
+  ApiError {
+    error: format!("proposal {} doesn't exist", id),
+    code: StatusCode::NOT_FOUND,
+    message: None,
+    details: Vec::new(),
+  }
+}
+
+/// retrieves proposal info by id, along with the info needed to format it into a Cosmos proposal
+pub(crate) async fn get_proposal(app_state: Arc<AppState>, id: u64, height: QueryHeight, requested_status: Option<u32>) -> Result<Option<ProposalData>, ApiError> {
+  // proposals submitted after the height didn't exist yet
+  if height.0.is_some() && id >= get_proposal_count(&app_state, height).await? {
+    return Ok(None);
+  }
+
+  let current_epoch = query_epoch_at(&app_state, height).await?;
+  // the rpc query may return None; also, if the status doesn't match the filter parmas we will return None
+  // neither of these cases will be considered errors, it just means nothing needs to be appended to the eventual Api response
+  match get_storage_proposal(&app_state, id).await? {
     Some(proposal) => {
-      let proposal_status = proposal.get_status(current_epoch);
-      let proposal_result = get_proposal_result(app_state.clone(), id).await?;
-
-      if let Some(proposal_result) = proposal_result {
-//
-        let status: CosmosProposalStatus = map_status_namada_to_cosmos(proposal_status, proposal_result.result);
-
-        if filter_proposal_by_status(requested_status, status) {
-          let mut final_tally_result = FinalTallyInfo::default();
-          final_tally_result.yes = proposal_result.total_yay_power.to_string_native();
-          final_tally_result.no = proposal_result.total_nay_power.to_string_native();
-
-          // TODO: how do we find this value?
-          let submit_time = DEFAULT_TIMESTAMP.to_string();
-          // TODO: properly convert these from epochs to timestamps
-          let deposit_end_time = proposal.voting_start_epoch.to_string(); // same as voting_start_time?
-
-          // get deposit amount
-          let gov_params = rpc::query_governance_parameters(app_state.get_client()).await;
-          let deposit = DenomAmount::nam_amount(gov_params.min_proposal_fund);
-
-          // TODO: properly convert these from epochs to timestamps
-          let voting_start_time = proposal.voting_start_epoch.to_string();
-          let voting_end_time = proposal.voting_end_epoch.to_string();
-
-          Ok(Some(ProposalItem {
-            proposal_id: proposal.id.to_string(),
-            content: ProposalInfo {
-              at_type: proposal.r#type.to_string(),
-              title: proposal.content.get("title").unwrap_or(&"".to_string()).clone(),
-              // TODO: concatenate 'abstract', 'motivation', and 'details'?
-              description: proposal.content.get("details").unwrap_or(&"".to_string()).clone(),
-              recipient: None,
-              amount: None,
-            },
-            status,
-            final_tally_result,
-            submit_time,
-            deposit_end_time,
-            total_deposit: vec![deposit],
-            voting_start_time,
-            voting_end_time,
-          }))
-        }
-        else { Ok(None) }
-      //
-      }
-      else { Ok(None) } // if proposal doesn't match requested status
+      // get deposit amount
+      let gov_params = rpc::query_governance_parameters(app_state.get_client()).await;
+      let deposit = DenomAmount::nam_amount(gov_params.min_proposal_fund);
+
+      let proposal_data = get_proposal_data(app_state, proposal, current_epoch, deposit, height).await?;
+      Ok(proposal_data.filter(|data| filter_proposal_by_status(requested_status, data.status)))
     }
     None => Ok(None) // if rpc query doesn't find a proposal matching the id
   }
 }
 
+/// Looks up the status and tally of a proposal
+async fn get_proposal_data(app_state: Arc<AppState>, proposal: StorageProposal, current_epoch: Epoch, deposit: DenomAmount, height: QueryHeight) -> Result<Option<ProposalData>, ApiError> {
+  let proposal_status = proposal.get_status(current_epoch);
+
+  // voting hasn't started yet, so there is nothing to tally
+  if let ProposalStatus::Pending = proposal_status {
+    return Ok(Some(ProposalData {
+      proposal,
+      status: CosmosProposalStatus::PROPOSAL_STATUS_DEPOSIT_PERIOD,
+      final_tally_result: FinalTallyInfo::default(),
+      deposit,
+    }))
+  }
+
+  match get_proposal_result(app_state, proposal.id, current_epoch, height).await? {
+    Some(proposal_result) => {
+      let status: CosmosProposalStatus = map_status_namada_to_cosmos(proposal_status, proposal_result.result);
+
+      let mut final_tally_result = FinalTallyInfo::default();
+      final_tally_result.yes = proposal_result.total_yay_power.to_string_native();
+      final_tally_result.no = proposal_result.total_nay_power.to_string_native();
+
+      Ok(Some(ProposalData {
+        proposal,
+        status,
+        final_tally_result,
+        deposit,
+      }))
+    }
+    // at a past height there is no live tally; like the Cosmos-sdk's, the final tally stays empty until voting ends
+    None if height.0.is_some() && matches!(proposal_status, ProposalStatus::OnGoing) => Ok(Some(ProposalData {
+      proposal,
+      status: CosmosProposalStatus::PROPOSAL_STATUS_VOTING_PERIOD,
+      final_tally_result: FinalTallyInfo::default(),
+      deposit,
+    })),
+    None => Ok(None)
+  }
+}
+
+/// Queries a proposal by id, using the cached copy if we've seen it before (proposals can't be modified once submitted)
+async fn get_storage_proposal(app_state: &AppState, id: u64) -> Result<Option<StorageProposal>, ApiError> {
+  if let Some(proposal) = app_state.proposal_cache.get_proposal(id) {
+    return Ok(Some(proposal));
+  }
+
+  let proposal = rpc::query_proposal_by_id(app_state.get_client(), id).await?;
+  if let Some(proposal) = &proposal {
+    app_state.proposal_cache.insert_proposal(proposal.clone());
+  }
+
+  Ok(proposal)
+}
+
 /// Attempts to query a proposal result from storage
-async fn get_proposal_result(app_state: Arc<AppState>, id: u64) -> Result<Option<ProposalResult>, ApiError> {
-  // let proposal_result_key: Key = governance_storage::get_proposal_result_key(id);
-  // let proposal_result: ProposalResult = rpc::query_storage_value(app_state.get_client(), &proposal_result_key).await?;
+/// Results of proposals that have finished voting are final, so those are cached
+/// At a past height, only the final results stored by then are read; the sdk computes live tallies from the latest votes
+async fn get_proposal_result(app_state: Arc<AppState>, id: u64, current_epoch: Epoch, height: QueryHeight) -> Result<Option<ProposalResult>, ApiError> {
+  if height.0.is_some() {
+    let voting_ended = get_storage_proposal(&app_state, id).await?
+      .map(|proposal| matches!(proposal.get_status(current_epoch), ProposalStatus::Ended))
+      .unwrap_or(false);
+    if !voting_ended {
+      return Ok(None);
+    }
+  }
+
+  if let Some(proposal_result) = app_state.proposal_cache.get_final_result(id) {
+    return Ok(Some(proposal_result));
+  }
+
+  if height.0.is_some() {
+    let proposal_result_key: Key = governance_storage::get_proposal_result_key(id);
+    return query_storage_value_at(&app_state, &proposal_result_key, height).await;
+  }
+
   match rpc::query_proposal_result(app_state.get_client(), id).await? {
-    Some(proposal_result) => Ok(Some(proposal_result)),
+    Some(proposal_result) => {
+      let voting_ended = get_storage_proposal(&app_state, id).await?
+        .map(|proposal| matches!(proposal.get_status(current_epoch), ProposalStatus::Ended))
+        .unwrap_or(false);
+      if voting_ended {
+        app_state.proposal_cache.insert_final_result(id, proposal_result.clone());
+      }
+      Ok(Some(proposal_result))
+    },
     None => Ok(None)
   }
 
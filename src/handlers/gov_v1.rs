@@ -0,0 +1,125 @@
+use axum::{
+  extract::{Path, Query, State}, http::{HeaderMap, StatusCode}, Json
+};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  sync::Arc,
+};
+use namada_sdk::{proof_of_stake::Epoch, types::dec::Dec};
+
+use crate::app::app_state::AppState;
+use crate::error::api_error::ApiError;
+use crate::handlers::shared::{epoch_start_time, freshness_headers, QueryHeight};
+use crate::handlers::gov::{get_all_proposals, get_deposit_params, get_proposal, get_proposal_tally, proposal_not_found, get_tally_params, get_voting_params, PROPOSAL_QUERY_CONCURRENCY};
+use crate::model::{
+  gov::ProposalData,
+  gov_v1::{
+    GovV1Params, IndividualProposalV1Response, ParamsGovV1Response, ProposalV1Item, ProposalV1QueryParams, ProposalsV1Response, TallyV1Info, TallyV1Response,
+  },
+  shared::DEFAULT_TIMESTAMP,
+};
+
+
+pub async fn params_handler(Path(params_type): Path<String>, State(app_state): State<Arc<AppState>>)
+  -> Result<Json<ParamsGovV1Response>, ApiError> {
+
+  let voting_params = get_voting_params(&app_state).await?;
+  let deposit_params = get_deposit_params(&app_state).await;
+  let tally_params = get_tally_params();
+
+  let params = GovV1Params {
+    min_deposit: deposit_params.min_deposit.clone(),
+    max_deposit_period: deposit_params.max_deposit_period.clone(),
+    voting_period: voting_params.voting_period.clone(),
+    quorum: tally_params.quorum,
+    threshold: tally_params.threshold,
+    veto_threshold: tally_params.veto_threshold,
+    // Namada requires the full proposal fund to be locked when the proposal is submitted
+    min_initial_deposit_ratio: Dec::one(),
+    burn_vote_quorum: false,
+    burn_proposal_deposit_prevote: false,
+    burn_vote_veto: false,
+  };
+
+  // like the Cosmos sdk, only the deprecated object matching the requested type is filled in
+  let response = match params_type.as_str() {
+    "voting" => ParamsGovV1Response { voting_params: Some(voting_params), deposit_params: None, tally_params: None, params },
+    "deposit" => ParamsGovV1Response { voting_params: None, deposit_params: Some(deposit_params), tally_params: None, params },
+    "tallying" => ParamsGovV1Response { voting_params: None, deposit_params: None, tally_params: Some(tally_params), params },
+    _ => return Err(ApiError {
+      error: format!("unknown params type: {}", params_type),
+      code: StatusCode::BAD_REQUEST,
+      message: Some("params type must be one of 'voting', 'deposit' or 'tallying'".to_string()),
+      details: Vec::new(),
+    }),
+  };
+
+  Ok(Json(response))
+}
+
+pub async fn all_proposals_handler(query: Query<ProposalV1QueryParams>, height: QueryHeight, State(app_state): State<Arc<AppState>>)
+  -> Result<(HeaderMap, Json<ProposalsV1Response>), ApiError> {
+
+  let (proposals, pagination, freshness) = get_all_proposals(Arc::clone(&app_state), query.proposal_status, &query.pagination, height).await?;
+
+  let response = ProposalsV1Response {
+    proposals: proposal_items(&app_state, proposals).await?,
+    pagination: Some(pagination),
+  };
+
+  let mut headers = freshness_headers(freshness);
+  headers.extend(height.headers());
+
+  Ok((headers, Json(response)))
+}
+
+pub async fn single_proposal_handler(Path(id): Path<u64>, height: QueryHeight, State(app_state): State<Arc<AppState>>)
+  -> Result<(HeaderMap, Json<IndividualProposalV1Response>), ApiError> {
+
+  let proposal = get_proposal(Arc::clone(&app_state), id, height, None).await?.ok_or_else(|| proposal_not_found(id))?;
+  let response = IndividualProposalV1Response { proposal: proposal_items(&app_state, vec![proposal]).await?.pop() };
+
+  Ok((height.headers(), Json(response)))
+}
+
+pub async fn proposal_tally_handler(Path(id): Path<u64>, height: QueryHeight, State(app_state): State<Arc<AppState>>)
+  -> Result<(HeaderMap, Json<TallyV1Response>), ApiError> {
+
+  let tally = get_proposal_tally(app_state, id, height).await?;
+  let response = TallyV1Response { tally: TallyV1Info::from(tally) };
+
+  Ok((height.headers(), Json(response)))
+}
+
+/// Converts proposals to v1 items, timed by the start of their voting epochs (each distinct epoch looked up once, and
+/// cached until the next epoch). The deposit ends when voting starts, since Namada locks the full deposit on submission.
+/// Epochs that haven't started yet, or started before the node's earliest block, keep the default timestamp
+async fn proposal_items(app_state: &AppState, proposals: Vec<ProposalData>) -> Result<Vec<ProposalV1Item>, ApiError> {
+  let epochs: BTreeSet<Epoch> = proposals.iter()
+    .flat_map(|data| [data.proposal.voting_start_epoch, data.proposal.voting_end_epoch])
+    .collect();
+  let start_times: BTreeMap<Epoch, Option<String>> = stream::iter(epochs)
+    .map(|epoch| async move {
+      let start_time = epoch_start_time(app_state, epoch).await?;
+      Ok::<_, ApiError>((epoch, start_time.map(|time| time.to_rfc3339())))
+    })
+    .buffered(PROPOSAL_QUERY_CONCURRENCY)
+    .try_collect()
+    .await?;
+  let start_time = |epoch: Epoch| start_times.get(&epoch).cloned().flatten().unwrap_or_else(|| DEFAULT_TIMESTAMP.to_string());
+
+  let items = proposals.into_iter()
+    .map(|data| {
+      let (voting_start_epoch, voting_end_epoch) = (data.proposal.voting_start_epoch, data.proposal.voting_end_epoch);
+      ProposalV1Item {
+        deposit_end_time: start_time(voting_start_epoch),
+        voting_start_time: start_time(voting_start_epoch),
+        voting_end_time: start_time(voting_end_epoch),
+        ..ProposalV1Item::from(data)
+      }
+    })
+    .collect();
+
+  Ok(items)
+}
@@ -1 +1,413 @@
-// nothing here yet!
\ No newline at end of file
+use axum::{
+  extract::{Path, Query, State},
+  http::StatusCode,
+  Json,
+};
+use std::{
+  collections::BTreeMap,
+  sync::Arc,
+};
+use prost::Message;
+use namada_sdk::{
+  ibc::storage::ibc_key,
+  queries::RPC,
+  rpc,
+  types::storage::{Key, PrefixValue},
+};
+
+use crate::app::app_state::AppState;
+use crate::error::api_error::ApiError;
+use crate::model::ibc::{
+  proto, ChannelResponse, ChannelsResponse, ClientStateInfo, ClientStatesResponse, ConnectionsResponse, DenomTrace, DenomTraceResponse, DenomTracesResponse, HeightInfo, IbcQueryParams,
+  IdentifiedChannel, IdentifiedClientState, IdentifiedConnection, PacketAcknowledgementsResponse, PacketCommitmentsResponse, PacketState,
+  TendermintClientStateInfo, UnreceivedSequencesResponse, TENDERMINT_CLIENT_STATE_TYPE_URL,
+};
+use base64::{encode_config, STANDARD};
+
+/// proto value of ibc.core.channel.v1.Order::ORDER_ORDERED
+const ORDER_ORDERED: i32 = 2;
+
+
+pub async fn channels_handler(query: Query<IbcQueryParams>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<ChannelsResponse>, ApiError> {
+
+  // channel ends are stored under 'channelEnds/ports/{port_id}/channels/{channel_id}'
+  let mut channels = Vec::new();
+  for (path, value) in query_ibc_prefix(&app_state, "channelEnds").await? {
+    if let [_, _, port_id, _, channel_id] = path.as_slice() {
+      let channel = decode_ibc_value::<proto::Channel>(&value)?;
+      channels.push(IdentifiedChannel::new(port_id.clone(), channel_id.clone(), channel));
+    }
+  }
+
+  let (channels, pagination) = query.pagination.paginate(channels);
+  let response = ChannelsResponse {
+    channels,
+    pagination,
+    height: HeightInfo::namada_height(query_latest_height(&app_state).await?),
+  };
+
+  Ok(Json(response))
+}
+
+pub async fn channel_handler(Path((channel_id, port_id)): Path<(String, String)>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<ChannelResponse>, ApiError> {
+
+  let channel_path = format!("channelEnds/ports/{}/channels/{}", port_id, channel_id);
+  let channel = match query_ibc_value(&app_state, &channel_path).await? {
+    Some(value) => decode_ibc_value::<proto::Channel>(&value)?,
+    None => return Err(ApiError {
+      error: format!("channel {} on port {} not found", channel_id, port_id),
+      code: StatusCode::NOT_FOUND,
+      message: None,
+      details: Vec::new(),
+    }),
+  };
+
+  // the channel's connection is the first (and for now, only) connection hop
+  let connection = match channel.connection_hops.first() {
+    Some(connection_id) => query_ibc_value(&app_state, &format!("connections/{}", connection_id)).await?
+      .map(|value| decode_ibc_value::<proto::ConnectionEnd>(&value))
+      .transpose()?
+      .map(|connection| IdentifiedConnection::new(connection_id.clone(), connection)),
+    None => None,
+  };
+
+  let client_state = match &connection {
+    Some(connection) => query_ibc_value(&app_state, &format!("clients/{}/clientState", connection.client_id)).await?
+      .map(|value| decode_client_state(&value))
+      .transpose()?
+      .map(|client_state| IdentifiedClientState { client_id: connection.client_id.clone(), client_state }),
+    None => None,
+  };
+
+  let response = ChannelResponse {
+    channel: IdentifiedChannel::new(port_id, channel_id, channel),
+    connection,
+    client_state,
+    proof: None,
+    proof_height: HeightInfo::namada_height(query_latest_height(&app_state).await?),
+  };
+
+  Ok(Json(response))
+}
+
+pub async fn packet_commitments_handler(Path((channel_id, port_id)): Path<(String, String)>, query: Query<IbcQueryParams>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<PacketCommitmentsResponse>, ApiError> {
+
+  // commitments of packets sent by Namada that haven't been acknowledged (or timed out) yet
+  let commitments = query_packet_states(&app_state, "commitments", &port_id, &channel_id).await?;
+
+  let (commitments, pagination) = query.pagination.paginate(commitments);
+  let response = PacketCommitmentsResponse {
+    commitments,
+    pagination,
+    height: HeightInfo::namada_height(query_latest_height(&app_state).await?),
+  };
+
+  Ok(Json(response))
+}
+
+pub async fn packet_acknowledgements_handler(Path((channel_id, port_id)): Path<(String, String)>, query: Query<IbcQueryParams>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<PacketAcknowledgementsResponse>, ApiError> {
+
+  // acknowledgements written by Namada for packets it received
+  let acknowledgements = query_packet_states(&app_state, "acks", &port_id, &channel_id).await?;
+
+  let (acknowledgements, pagination) = query.pagination.paginate(acknowledgements);
+  let response = PacketAcknowledgementsResponse {
+    acknowledgements,
+    pagination,
+    height: HeightInfo::namada_height(query_latest_height(&app_state).await?),
+  };
+
+  Ok(Json(response))
+}
+
+/// Given the sequences of packets committed on the counterparty chain, returns those that Namada hasn't received
+pub async fn unreceived_packets_handler(Path((channel_id, port_id, sequences)): Path<(String, String, String)>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<UnreceivedSequencesResponse>, ApiError> {
+
+  let sequences = parse_sequences(&sequences)?;
+  let channel_path = format!("channelEnds/ports/{}/channels/{}", port_id, channel_id);
+  let ordering = match query_ibc_value(&app_state, &channel_path).await? {
+    Some(value) => decode_ibc_value::<proto::Channel>(&value)?.ordering,
+    None => return Err(ApiError {
+      error: format!("channel {} on port {} not found", channel_id, port_id),
+      code: StatusCode::NOT_FOUND,
+      message: None,
+      details: Vec::new(),
+    }),
+  };
+
+  let mut unreceived = Vec::new();
+  // ordered channels receive packets in sequence, so anything from the next expected sequence onwards is unreceived
+  if ordering == ORDER_ORDERED {
+    let next_sequence_path = format!("nextSequenceRecv/ports/{}/channels/{}", port_id, channel_id);
+    let next_sequence = query_ibc_value(&app_state, &next_sequence_path).await?
+      .and_then(|value| <[u8; 8]>::try_from(value.as_slice()).ok())
+      .map(u64::from_be_bytes)
+      .unwrap_or(1);
+    unreceived.extend(sequences.into_iter().filter(|sequence| *sequence >= next_sequence));
+  } else {
+    // unordered channels write a receipt for every packet they receive
+    for sequence in sequences {
+      let receipt_path = format!("receipts/ports/{}/channels/{}/sequences/{}", port_id, channel_id, sequence);
+      if !rpc::query_has_storage_key(app_state.get_client(), &ibc_path_key(&receipt_path)?).await? {
+        unreceived.push(sequence);
+      }
+    }
+  }
+
+  let response = UnreceivedSequencesResponse {
+    sequences: unreceived.into_iter().map(|sequence| sequence.to_string()).collect(),
+    height: HeightInfo::namada_height(query_latest_height(&app_state).await?),
+  };
+
+  Ok(Json(response))
+}
+
+/// Given the sequences of packets acknowledged on the counterparty chain, returns those whose acknowledgement Namada
+/// hasn't processed yet, ie: the packet commitment still exists
+pub async fn unreceived_acks_handler(Path((channel_id, port_id, sequences)): Path<(String, String, String)>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<UnreceivedSequencesResponse>, ApiError> {
+
+  let mut unreceived = Vec::new();
+  for sequence in parse_sequences(&sequences)? {
+    let commitment_path = format!("commitments/ports/{}/channels/{}/sequences/{}", port_id, channel_id, sequence);
+    if rpc::query_has_storage_key(app_state.get_client(), &ibc_path_key(&commitment_path)?).await? {
+      unreceived.push(sequence);
+    }
+  }
+
+  let response = UnreceivedSequencesResponse {
+    sequences: unreceived.into_iter().map(|sequence| sequence.to_string()).collect(),
+    height: HeightInfo::namada_height(query_latest_height(&app_state).await?),
+  };
+
+  Ok(Json(response))
+}
+
+pub async fn connections_handler(query: Query<IbcQueryParams>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<ConnectionsResponse>, ApiError> {
+
+  // connection ends are stored under 'connections/{connection_id}'
+  let mut connections = Vec::new();
+  for (path, value) in query_ibc_prefix(&app_state, "connections").await? {
+    if let [_, connection_id] = path.as_slice() {
+      let connection = decode_ibc_value::<proto::ConnectionEnd>(&value)?;
+      connections.push(IdentifiedConnection::new(connection_id.clone(), connection));
+    }
+  }
+
+  let (connections, pagination) = query.pagination.paginate(connections);
+  let response = ConnectionsResponse {
+    connections,
+    pagination,
+    height: HeightInfo::namada_height(query_latest_height(&app_state).await?),
+  };
+
+  Ok(Json(response))
+}
+
+pub async fn client_states_handler(query: Query<IbcQueryParams>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<ClientStatesResponse>, ApiError> {
+
+  // client states are stored under 'clients/{client_id}/clientState', alongside the clients' consensus states etc.
+  let mut client_states = Vec::new();
+  for (path, value) in query_ibc_prefix(&app_state, "clients").await? {
+    if let [_, client_id, state] = path.as_slice() {
+      if state == "clientState" {
+        client_states.push(IdentifiedClientState {
+          client_id: client_id.clone(),
+          client_state: decode_client_state(&value)?,
+        });
+      }
+    }
+  }
+
+  let (client_states, pagination) = query.pagination.paginate(client_states);
+  let response = ClientStatesResponse {
+    client_states,
+    pagination,
+  };
+
+  Ok(Json(response))
+}
+
+pub async fn denom_traces_handler(query: Query<IbcQueryParams>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<DenomTracesResponse>, ApiError> {
+
+  let denom_traces: Vec<DenomTrace> = get_ibc_denoms(&app_state).await?
+    .values()
+    .map(|denom| DenomTrace::from_full_denom(denom))
+    .collect();
+
+  let (denom_traces, pagination) = query.pagination.paginate(denom_traces);
+  let response = DenomTracesResponse {
+    denom_traces,
+    pagination,
+  };
+
+  Ok(Json(response))
+}
+
+pub async fn denom_trace_handler(Path(hash): Path<String>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<DenomTraceResponse>, ApiError> {
+
+  // accept the hash on its own or as the full 'ibc/{hash}' denom
+  let hash = hash.trim_start_matches("ibc/").to_uppercase();
+  let denoms = get_ibc_denoms(&app_state).await?;
+
+  match denoms.get(&hash) {
+    Some(denom) => Ok(Json(DenomTraceResponse { denom_trace: DenomTrace::from_full_denom(denom) })),
+    None => Err(ApiError {
+      error: format!("denomination trace not found: {}", hash),
+      code: StatusCode::NOT_FOUND,
+      message: None,
+      details: Vec::new(),
+    }),
+  }
+}
+
+/// Reads the full denoms of all IBC tokens received by Namada, keyed by their (uppercase) hash
+/// Namada stores these under 'ibc_denom/{receiver}/{hash}', so the same token can appear once per receiver
+pub(crate) async fn get_ibc_denoms(app_state: &AppState) -> Result<BTreeMap<String, String>, ApiError> {
+  let mut denoms = BTreeMap::new();
+  for (path, value) in query_ibc_prefix(app_state, "ibc_denom").await? {
+    if let [_, _, hash] = path.as_slice() {
+      let denom = match borsh::from_slice::<String>(&value) {
+        Ok(denom) => denom,
+        Err(_) => String::from_utf8_lossy(&value).to_string(),
+      };
+      denoms.insert(hash.to_uppercase(), denom);
+    }
+  }
+
+  Ok(denoms)
+}
+
+/// Reads the packet commitments ('commitments') or acknowledgements ('acks') stored for a channel, ordered by sequence
+async fn query_packet_states(app_state: &AppState, kind: &str, port_id: &str, channel_id: &str) -> Result<Vec<PacketState>, ApiError> {
+  // stored under '{kind}/ports/{port_id}/channels/{channel_id}/sequences/{sequence}'
+  let prefix = format!("{}/ports/{}/channels/{}/sequences", kind, port_id, channel_id);
+  let mut packet_states: Vec<(u64, Vec<u8>)> = query_ibc_prefix(app_state, &prefix).await?
+    .into_iter()
+    .filter_map(|(path, value)| {
+      let sequence = path.last()?.parse::<u64>().ok()?;
+      Some((sequence, value))
+    })
+    .collect();
+  packet_states.sort_by_key(|(sequence, _)| *sequence);
+
+  Ok(packet_states.into_iter()
+    .map(|(sequence, value)| PacketState {
+      port_id: port_id.to_string(),
+      channel_id: channel_id.to_string(),
+      sequence: sequence.to_string(),
+      data: encode_config(value, STANDARD),
+    })
+    .collect())
+}
+
+/// Parses a comma separated list of packet sequences, eg: '1,2,3'
+fn parse_sequences(sequences: &str) -> Result<Vec<u64>, ApiError> {
+  sequences.split(',')
+    .filter(|sequence| !sequence.is_empty())
+    .map(|sequence| sequence.trim().parse::<u64>().map_err(|err| ApiError {
+      error: err.to_string(),
+      code: StatusCode::BAD_REQUEST,
+      message: Some(format!("invalid packet sequence: {}", sequence)),
+      details: Vec::new(),
+    }))
+    .collect()
+}
+
+/// Reads a single IBC storage value, eg: 'connections/connection-0'
+pub(crate) async fn query_ibc_value(app_state: &AppState, path: &str) -> Result<Option<Vec<u8>>, ApiError> {
+  let (value, _) = rpc::query_storage_value_bytes(app_state.get_client(), &ibc_path_key(path)?, None, false).await?;
+  Ok(value)
+}
+
+fn ibc_path_key(path: &str) -> Result<Key, ApiError> {
+  ibc_key(path).map_err(|err| ApiError {
+    error: err.to_string(),
+    code: StatusCode::BAD_REQUEST,
+    message: Some(format!("invalid ibc path: {}", path)),
+    details: Vec::new(),
+  })
+}
+
+/// Queries all of the IBC storage values under a path, eg: 'channelEnds'
+/// Returns each value along with its IBC path split into segments
+pub(crate) async fn query_ibc_prefix(app_state: &AppState, path: &str) -> Result<Vec<(Vec<String>, Vec<u8>)>, ApiError> {
+  let prefix = ibc_path_key(path)?;
+
+  let response = RPC.shell().storage_prefix(app_state.get_client(), None, None, false, &prefix).await
+    .map_err(|err| ApiError::from_query_message(err.to_string()))?;
+
+  // the first key segment is the IBC internal address
+  let values = response.data.into_iter()
+    .map(|PrefixValue { key, value }| {
+      let path = key.segments.iter().skip(1).map(|segment| segment.raw()).collect();
+      (path, value)
+    })
+    .collect();
+
+  Ok(values)
+}
+
+/// Decodes a protobuf encoded IBC state
+pub(crate) fn decode_ibc_value<T: Message + Default>(value: &[u8]) -> Result<T, ApiError> {
+  T::decode(value).map_err(|err| ApiError {
+    error: err.to_string(),
+    code: StatusCode::INTERNAL_SERVER_ERROR,
+    message: Some("failed to decode ibc state".to_string()),
+    details: Vec::new(),
+  })
+}
+
+/// Client states are stored as protobuf `Any`s; only Tendermint client states are decoded any further
+pub(crate) fn decode_client_state(value: &[u8]) -> Result<ClientStateInfo, ApiError> {
+  let any = decode_ibc_value::<prost_types::Any>(value)?;
+  let tendermint = if any.type_url == TENDERMINT_CLIENT_STATE_TYPE_URL {
+    Some(TendermintClientStateInfo::from(decode_ibc_value::<proto::TendermintClientState>(&any.value)?))
+  } else {
+    None
+  };
+
+  Ok(ClientStateInfo {
+    at_type: any.type_url,
+    tendermint,
+  })
+}
+
+pub(crate) async fn query_latest_height(app_state: &AppState) -> Result<u64, ApiError> {
+  let last_block = rpc::query_block(app_state.get_client()).await?;
+  Ok(last_block.map(|block| block.height.0).unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_comma_separated_sequences() {
+    assert_eq!(parse_sequences("1,2,3").unwrap(), vec![1, 2, 3]);
+    assert_eq!(parse_sequences("1, 2").unwrap(), vec![1, 2]);
+  }
+
+  #[test]
+  fn skips_empty_sequences() {
+    assert_eq!(parse_sequences("").unwrap(), Vec::<u64>::new());
+    assert_eq!(parse_sequences("1,,2,").unwrap(), vec![1, 2]);
+  }
+
+  #[test]
+  fn rejects_invalid_sequences() {
+    let error = parse_sequences("1,x").unwrap_err();
+    assert_eq!(error.code, StatusCode::BAD_REQUEST);
+    assert_eq!(error.message.as_deref(), Some("invalid packet sequence: x"));
+    assert!(parse_sequences("-1").is_err());
+  }
+}
@@ -1,8 +1,18 @@
+pub mod auth;
 pub mod bank;
 pub mod distribution;
+pub mod fallback;
 pub mod gov;
-// pub mod ibc;
+pub mod gov_v1;
+pub mod ibc;
 pub mod mint;
+pub mod pgf;
+pub mod shared;
+pub mod simulate;
 pub mod slashing;
 pub mod staking;
+pub mod status;
 pub mod tendermint;
+#[cfg(feature = "indexer")]
+pub mod tx;
+pub mod uptime;
@@ -0,0 +1,56 @@
+use axum::{
+  extract::State,
+  Json,
+};
+use std::sync::Arc;
+use namada_sdk::rpc;
+
+use crate::app::app_state::AppState;
+use crate::error::api_error::ApiError;
+use crate::model::{
+  shared::DenomAmount,
+  pgf::{FundingInfo, FundingsResponse, RewardShare, StewardInfo, StewardsResponse},
+};
+
+
+pub async fn stewards_handler(State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<StewardsResponse>, ApiError> {
+
+  let pgf_params = rpc::query_pgf_parameters(app_state.get_client()).await;
+  let stewards = rpc::query_pgf_stewards(app_state.get_client()).await;
+
+  let response = StewardsResponse {
+    stewards: stewards.into_iter().map(|steward| {
+      StewardInfo {
+        address: steward.address,
+        reward_distribution: steward.reward_distribution.into_iter()
+          .map(|(address, share)| RewardShare { address, share })
+          .collect(),
+      }
+    }).collect(),
+    stewards_inflation_rate: pgf_params.stewards_inflation_rate,
+  };
+
+  Ok(Json(response))
+}
+
+pub async fn fundings_handler(State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<FundingsResponse>, ApiError> {
+
+  let pgf_params = rpc::query_pgf_parameters(app_state.get_client()).await;
+  let fundings = rpc::query_pgf_fundings(app_state.get_client()).await;
+
+  let response = FundingsResponse {
+    fundings: fundings.into_iter().map(|funding| {
+      FundingInfo {
+        id: funding.id.to_string(),
+        target: funding.detail.target(),
+        // continuous funding is always paid in the native token
+        amount: DenomAmount::nam_amount(funding.detail.amount()),
+      }
+    }).collect(),
+    pgf_inflation_rate: pgf_params.pgf_inflation_rate,
+  };
+
+  Ok(Json(response))
+}
@@ -0,0 +1,372 @@
+use axum::{
+  async_trait,
+  extract::{FromRequestParts, Request},
+  http::{request::Parts, HeaderMap, HeaderValue, StatusCode},
+  middleware::Next,
+  response::{IntoResponse, Response},
+};
+use borsh::BorshDeserialize;
+use futures::future::try_join_all;
+use std::future::Future;
+use tendermint::{block::Height, Time};
+use tendermint_rpc::Client;
+use namada_sdk::{
+  Namada,
+  proof_of_stake::Epoch,
+  rpc,
+  types::{address::Address, key::tm_consensus_key_raw_hash, storage::{BlockHeight, Key}, token::{self, Amount}},
+};
+
+use crate::app::{app_state::AppState, chain_follower::SnapshotFreshness, response_cache::CacheScope};
+use crate::error::api_error::ApiError;
+use crate::model::shared::DenomAmount;
+
+
+/// Returns the cached response for `category`/`key` if it's still current, otherwise computes and caches it
+/// Errors aren't cached
+pub(crate) async fn cached<T, F, Fut>(app_state: &AppState, category: &'static str, key: &str, scope: CacheScope, compute: F) -> Result<T, ApiError>
+where
+  T: Clone + Send + Sync + 'static,
+  F: FnOnce() -> Fut,
+  Fut: Future<Output = Result<T, ApiError>>,
+{
+  if let Some(value) = app_state.response_cache.get::<T>(category, key) {
+    return Ok(value);
+  }
+
+  // tag the value with the position from before it was computed, so a block/epoch change mid-query discards it
+  let position = app_state.response_cache.position();
+  let value = compute().await?;
+  app_state.response_cache.insert(category, key, scope, position, value.clone());
+
+  Ok(value)
+}
+
+/// Headers telling the client how current a response served from the chain snapshot is; empty for live responses
+pub(crate) fn freshness_headers(freshness: Option<SnapshotFreshness>) -> HeaderMap {
+  let mut headers = HeaderMap::new();
+  if let Some(freshness) = freshness {
+    headers.insert("x-snapshot-height", HeaderValue::from(freshness.height));
+    headers.insert("x-snapshot-epoch", HeaderValue::from(freshness.epoch.0));
+    if let Ok(updated_at) = HeaderValue::from_str(&freshness.updated_at) {
+      headers.insert("x-snapshot-updated-at", updated_at);
+    }
+  }
+
+  headers
+}
+
+/// Header Cosmos clients send to query the state at a past block, and that is echoed back in the response
+pub const BLOCK_HEIGHT_HEADER: &str = "x-cosmos-block-height";
+
+/// The block height a request asked to query the state at, from the `x-cosmos-block-height` header or the
+/// `height` query param; None (like height 0 in the Cosmos-sdk) means the latest state
+#[derive(Clone, Copy, Default)]
+pub struct QueryHeight(pub Option<u64>);
+
+impl QueryHeight {
+  pub fn block_height(&self) -> Option<BlockHeight> {
+    self.0.map(BlockHeight)
+  }
+
+  /// Echoes the requested height back to the client; empty for queries of the latest state
+  pub fn headers(&self) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(height) = self.0 {
+      headers.insert(BLOCK_HEIGHT_HEADER, HeaderValue::from(height));
+    }
+    headers
+  }
+
+  /// A cache key suffix, so that responses for past heights aren't mixed up with the latest ones
+  pub fn cache_key(&self) -> String {
+    self.0.map(|height| format!("@{}", height)).unwrap_or_default()
+  }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for QueryHeight {
+  type Rejection = ApiError;
+
+  async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+    let header = parts.headers.get(BLOCK_HEIGHT_HEADER)
+      .map(|value| value.to_str().unwrap_or_default().to_string());
+    let param = parts.uri.query()
+      .and_then(|query| query.split('&').find_map(|pair| pair.strip_prefix("height=")))
+      .map(str::to_string);
+
+    match header.or(param) {
+      None => Ok(QueryHeight(None)),
+      Some(height) => match height.trim().parse::<u64>() {
+        Ok(0) => Ok(QueryHeight(None)),
+        Ok(height) => Ok(QueryHeight(Some(height))),
+        Err(_) => Err(ApiError {
+          error: "invalid height".to_string(),
+          code: StatusCode::BAD_REQUEST,
+          message: Some(format!("height must be a non-negative integer, got '{}'", height)),
+          details: Vec::new(),
+        }),
+      },
+    }
+  }
+}
+
+/// Middleware for the endpoints that can only answer with the latest state (eg: the sdk's PoS and rewards queries
+/// don't take a height): a request for a past height is refused, rather than answered with the latest state
+pub async fn latest_state_only(request: Request, next: Next) -> Response {
+  let (mut parts, body) = request.into_parts();
+  match QueryHeight::from_request_parts(&mut parts, &()).await {
+    Ok(QueryHeight(None)) => next.run(Request::from_parts(parts, body)).await,
+    Ok(QueryHeight(Some(height))) => ApiError {
+      error: format!("{} can't be queried at height {}", parts.uri.path(), height),
+      code: StatusCode::NOT_IMPLEMENTED,
+      message: Some("this endpoint only serves the latest state".to_string()),
+      details: Vec::new(),
+    }.into_response(),
+    Err(error) => error.into_response(),
+  }
+}
+
+/// The epoch of the requested height, or the current epoch for the latest state
+pub(crate) async fn query_epoch_at(app_state: &AppState, height: QueryHeight) -> Result<Epoch, ApiError> {
+  let Some(block_height) = height.block_height() else {
+    return Ok(rpc::query_epoch(app_state.get_client()).await?);
+  };
+
+  rpc::query_epoch_at_height(app_state.get_client(), block_height).await?.ok_or(ApiError {
+    error: format!("height {} is not available", block_height),
+    code: StatusCode::BAD_REQUEST,
+    message: Some("the height may be in the future, or pruned from the node".to_string()),
+    details: Vec::new(),
+  })
+}
+
+/// Reads a storage value at the requested height (or the latest block); None if there is no value under the key
+pub(crate) async fn query_storage_value_at<T: BorshDeserialize>(app_state: &AppState, key: &Key, height: QueryHeight) -> Result<Option<T>, ApiError> {
+  let (value, _) = rpc::query_storage_value_bytes(app_state.get_client(), key, height.block_height(), false).await?;
+  match value {
+    Some(bytes) => T::try_from_slice(&bytes).map(Some).map_err(|err| ApiError {
+      error: format!("failed to decode the storage value of {}: {}", key, err),
+      code: StatusCode::INTERNAL_SERVER_ERROR,
+      message: None,
+      details: Vec::new(),
+    }),
+    None => Ok(None),
+  }
+}
+
+/// Queries an owner's balance of a token as of the height; zero if it never held any
+pub(crate) async fn query_balance_at(app_state: &AppState, token: &Address, owner: &Address, height: QueryHeight) -> Result<Amount, ApiError> {
+  let balance = query_storage_value_at(app_state, &token::balance_key(token, owner), height).await?;
+  Ok(balance.unwrap_or_default())
+}
+
+/// Queries an owner's balances as of the height: nam, then the other tokens it holds (eg: received over ibc) that
+/// aren't zero, all in whole tokens with each token's own decimals
+pub(crate) async fn query_balances_at(app_state: &AppState, owner: &Address, height: QueryHeight) -> Result<Vec<DenomAmount>, ApiError> {
+  let native_token = rpc::query_native_token(app_state.get_client()).await?;
+  let nam_balance = query_balance_at(app_state, &native_token, owner, height).await?;
+  let mut balances = vec![DenomAmount::nam_amount(nam_balance)];
+
+  let ibc_tokens = rpc::query_ibc_tokens(app_state.get_namada(), None, Some(owner)).await?;
+  let tasks = ibc_tokens.into_iter().map(|(denom, token)| async move {
+    let balance = query_balance_at(app_state, &token, owner, height).await?;
+    if balance.is_zero() {
+      return Ok::<_, ApiError>(None);
+    }
+    let amount = rpc::denominate_amount(app_state.get_client(), app_state.get_namada().io(), &token, balance).await;
+    Ok(Some(DenomAmount {
+      denom,
+      amount: amount.to_string(),
+    }))
+  });
+  balances.extend(try_join_all(tasks).await?.into_iter().flatten());
+
+  Ok(balances)
+}
+
+/// Resolves an address passed in as a path or query param to a native Namada address
+/// Besides native addresses, this accepts synthetic `valoper`/`valcons` addresses and hex consensus addresses
+pub(crate) async fn resolve_address(app_state: &AppState, address: &str) -> Result<Address, ApiError> {
+  if let Some(resolved) = app_state.address_map.resolve_account(address) {
+    return Ok(resolved);
+  }
+
+  if let Some(consensus_address) = app_state.address_map.parse_consensus_address(address) {
+    if let Some(validator) = app_state.address_map.validator_by_consensus(&consensus_address) {
+      return Ok(validator);
+    }
+    // the validator may have joined (or changed its consensus key) since the mapping was last built
+    ensure_consensus_addresses(app_state).await?;
+    return app_state.address_map.validator_by_consensus(&consensus_address).ok_or(ApiError {
+      error: format!("no validator found with consensus address {}", consensus_address),
+      code: StatusCode::NOT_FOUND,
+      message: None,
+      details: Vec::new(),
+    });
+  }
+
+  Err(ApiError {
+    error: "failed to decode address".to_string(),
+    code: StatusCode::BAD_REQUEST,
+    message: Some(format!("invalid address: {}", address)),
+    details: Vec::new(),
+  })
+}
+
+/// Rebuilds the consensus address <-> validator address mapping from the validators' consensus keys
+async fn refresh_consensus_addresses(app_state: &AppState, current_epoch: Epoch) -> Result<(), ApiError> {
+  let validators = rpc::get_all_validators(app_state.get_client(), current_epoch).await?;
+
+  let tasks = validators.into_iter().map(|validator| async move {
+    let consensus_key = rpc::query_validator_consensus_keys(app_state.get_client(), &validator).await?;
+    Ok::<_, ApiError>(consensus_key.map(|key| (validator, tm_consensus_key_raw_hash(&key))))
+  });
+  let entries: Vec<(Address, String)> = try_join_all(tasks).await?.into_iter().flatten().collect();
+
+  app_state.address_map.update_consensus_addresses(current_epoch, entries);
+  Ok(())
+}
+
+/// Rebuilds the consensus address mapping if it hasn't been built yet during the current epoch
+/// Consensus key changes only take effect at epoch boundaries, so the mapping can't go stale within an epoch
+/// The epoch is the chain follower's (only queried before the follower has seen a block), and a single request
+/// rebuilds the mapping while any others wait for it
+pub(crate) async fn ensure_consensus_addresses(app_state: &AppState) -> Result<(), ApiError> {
+  let current_epoch = match app_state.response_cache.position() {
+    Some(position) => Epoch(position.epoch),
+    None => rpc::query_epoch(app_state.get_client()).await?,
+  };
+  if !app_state.address_map.consensus_addresses_stale(current_epoch) {
+    return Ok(());
+  }
+
+  let _refreshing = app_state.address_map.lock_refresh().await;
+  if app_state.address_map.consensus_addresses_stale(current_epoch) {
+    refresh_consensus_addresses(app_state, current_epoch).await?;
+  }
+  Ok(())
+}
+
+/// Converts a block height to a CometBFT height, which doesn't use the full u64 range
+pub(crate) fn tendermint_height(height: u64) -> Result<Height, ApiError> {
+  Height::try_from(height).map_err(|err| ApiError {
+    error: format!("invalid height {}", height),
+    code: StatusCode::BAD_REQUEST,
+    message: Some(err.to_string()),
+    details: Vec::new(),
+  })
+}
+
+/// Finds the time at which an epoch started, ie: the time of its first block
+/// Returns None if the epoch hasn't started yet, or its first block has been pruned from the node
+/// Cached until the next epoch, including a None, so an epoch that hasn't started isn't searched for again meanwhile
+pub(crate) async fn epoch_start_time(app_state: &AppState, epoch: Epoch) -> Result<Option<Time>, ApiError> {
+  cached(app_state, "epoch_start_time", &epoch.0.to_string(), CacheScope::Epoch, || async {
+    match epoch_start_height(app_state, epoch).await? {
+      Some(height) => {
+        let block = app_state.get_client().block(tendermint_height(height)?).await?;
+        Ok(Some(block.block.header.time))
+      }
+      None => Ok(None)
+    }
+  }).await
+}
+
+/// Finds the first block height of an epoch by binary searching the block heights the node still has
+/// Returns None if the epoch hasn't started yet, or if it started before the earliest block the node has
+pub(crate) async fn epoch_start_height(app_state: &AppState, epoch: Epoch) -> Result<Option<u64>, ApiError> {
+  if let Some(height) = app_state.epoch_start_heights.read().unwrap().get(&epoch.0) {
+    return Ok(Some(*height));
+  }
+
+  let current_epoch = rpc::query_epoch(app_state.get_client()).await?;
+  if epoch > current_epoch {
+    return Ok(None);
+  }
+
+  let sync_info = app_state.get_client().status().await?.sync_info;
+  let mut low = sync_info.earliest_block_height.value().max(1);
+  let mut high = sync_info.latest_block_height.value();
+
+  // unless the node has every block, an epoch that was already under way at its earliest block started earlier
+  let earliest_epoch = available_epoch_at(app_state, low).await?;
+  if earliest_epoch > epoch || (earliest_epoch == epoch && low > 1) {
+    return Ok(None);
+  }
+
+  // find the lowest height whose epoch is >= the requested epoch
+  while low < high {
+    let mid = low + (high - low) / 2;
+    match available_epoch_at(app_state, mid).await? >= epoch {
+      true => high = mid,
+      false => low = mid + 1,
+    }
+  }
+
+  app_state.epoch_start_heights.write().unwrap().insert(epoch.0, low);
+  Ok(Some(low))
+}
+
+/// The epoch of a height within the node's available blocks, which it should always be able to answer
+async fn available_epoch_at(app_state: &AppState, height: u64) -> Result<Epoch, ApiError> {
+  rpc::query_epoch_at_height(app_state.get_client(), BlockHeight(height)).await?.ok_or(ApiError {
+    error: format!("epoch of height {} is not available", height),
+    code: StatusCode::SERVICE_UNAVAILABLE,
+    message: Some("the node may have pruned the height while it was being searched".to_string()),
+    details: Vec::new(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  async fn query_height(uri: &str, header: Option<&str>) -> Result<QueryHeight, ApiError> {
+    let mut request = Request::builder().uri(uri);
+    if let Some(header) = header {
+      request = request.header(BLOCK_HEIGHT_HEADER, header);
+    }
+    let (mut parts, _) = request.body(()).unwrap().into_parts();
+    QueryHeight::from_request_parts(&mut parts, &()).await
+  }
+
+  #[tokio::test]
+  async fn no_height_is_latest_state() {
+    assert_eq!(query_height("/cosmos/gov/v1/proposals", None).await.unwrap().0, None);
+    assert_eq!(query_height("/cosmos/gov/v1/proposals?heights=5", None).await.unwrap().0, None);
+  }
+
+  #[tokio::test]
+  async fn height_from_header_or_query() {
+    assert_eq!(query_height("/cosmos/gov/v1/proposals", Some("42")).await.unwrap().0, Some(42));
+    assert_eq!(query_height("/cosmos/gov/v1/proposals?proposal_status=2&height=7", None).await.unwrap().0, Some(7));
+  }
+
+  #[tokio::test]
+  async fn header_takes_precedence_over_query() {
+    assert_eq!(query_height("/cosmos/gov/v1/proposals?height=7", Some("42")).await.unwrap().0, Some(42));
+  }
+
+  #[tokio::test]
+  async fn zero_height_is_latest_state() {
+    assert_eq!(query_height("/cosmos/gov/v1/proposals", Some("0")).await.unwrap().0, None);
+    assert_eq!(query_height("/cosmos/gov/v1/proposals?height=0", None).await.unwrap().0, None);
+  }
+
+  #[tokio::test]
+  async fn invalid_height_is_rejected() {
+    for header in ["abc", "-1", "1.5"] {
+      let error = query_height("/cosmos/gov/v1/proposals", Some(header)).await.unwrap_err();
+      assert_eq!(error.code, StatusCode::BAD_REQUEST);
+    }
+  }
+
+  #[test]
+  fn height_is_echoed_back() {
+    assert_eq!(QueryHeight(Some(42)).headers().get(BLOCK_HEIGHT_HEADER).unwrap(), "42");
+    assert!(QueryHeight(None).headers().is_empty());
+    assert_eq!(QueryHeight(Some(42)).cache_key(), "@42");
+    assert_eq!(QueryHeight(None).cache_key(), "");
+  }
+}
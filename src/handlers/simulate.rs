@@ -0,0 +1,176 @@
+use axum::{
+  extract::State,
+  http::StatusCode,
+  Json,
+};
+use base64::decode;
+use std::sync::Arc;
+use namada_sdk::{
+  proto::Tx,
+  queries::{Error as QueryError, RPC},
+  types::transaction::{TxResult, TxType},
+};
+
+use crate::app::app_state::AppState;
+use crate::error::api_error::ApiError;
+use crate::model::{
+  shared::{EventAttribute, EventInfo},
+  simulate::{GasInfo, SimulateRequest, SimulateResponse, SimulateResult},
+};
+
+
+/// Runs a tx through the node's dry-run query, for wallets to estimate its gas before broadcasting it
+/// Like the Cosmos sdk, a tx that fails or is rejected by a validity predicate is an error rather than a result
+pub async fn simulate_handler(State(app_state): State<Arc<AppState>>, Json(request): Json<SimulateRequest>)
+  -> Result<Json<SimulateResponse>, ApiError> {
+
+  let (tx_bytes, gas_wanted) = parse_request(&request)?;
+
+  // queried through the router rather than `rpc::dry_run_tx`, which flattens the node's errors into a message
+  let result = match RPC.shell().dry_run_tx(app_state.get_client(), Some(tx_bytes), None, false).await {
+    Ok(response) => response.data,
+    // the node ran the tx and it failed (eg: a bad signature or an insufficient fee balance), which is the tx's fault
+    Err(QueryError::Query(info, code)) => return Err(ApiError {
+      error: "tx failed".to_string(),
+      code: StatusCode::BAD_REQUEST,
+      message: Some(format!("{} (code {})", info, code)),
+      details: Vec::new(),
+    }),
+    Err(QueryError::Tendermint(err)) => return Err(ApiError::from(err)),
+    Err(err) => return Err(ApiError {
+      error: err.to_string(),
+      code: StatusCode::INTERNAL_SERVER_ERROR,
+      message: None,
+      details: Vec::new(),
+    }),
+  };
+
+  if !result.is_accepted() {
+    return Err(ApiError {
+      error: "tx rejected".to_string(),
+      code: StatusCode::BAD_REQUEST,
+      message: Some(rejection_message(&result)),
+      details: Vec::new(),
+    });
+  }
+
+  let response = SimulateResponse {
+    gas_info: GasInfo {
+      gas_wanted: gas_wanted.to_string(),
+      gas_used: result.gas_used.to_string(),
+    },
+    result: SimulateResult {
+      data: String::new(),
+      log: String::new(),
+      events: tx_events(&result),
+      msg_responses: Vec::new(),
+    },
+  };
+
+  Ok(Json(response))
+}
+
+/// Decodes the tx to simulate, returning its bytes and the gas it wants
+fn parse_request(request: &SimulateRequest) -> Result<(Vec<u8>, u64), ApiError> {
+  let tx_bytes = match request.tx_bytes.as_deref() {
+    Some(tx_bytes) => decode(tx_bytes).map_err(|err| invalid_tx(format!("tx_bytes isn't valid base64: {}", err)))?,
+    None if request.tx.is_some() => return Err(invalid_tx("only tx_bytes is supported, the tx json can't describe a Namada tx".to_string())),
+    None => return Err(invalid_tx("tx_bytes is required".to_string())),
+  };
+  let tx = Tx::try_from(tx_bytes.as_slice()).map_err(|err| invalid_tx(format!("tx_bytes isn't a Namada tx: {}", err)))?;
+  // only a wrapper tx says how much gas it wants (and pays the fees), which is what a gas estimate is for
+  let gas_wanted = match &tx.header.tx_type {
+    TxType::Wrapper(wrapper) => u64::from(wrapper.gas_limit.clone()),
+    _ => return Err(invalid_tx("only wrapper txs can be simulated".to_string())),
+  };
+
+  Ok((tx_bytes, gas_wanted))
+}
+
+fn invalid_tx(message: String) -> ApiError {
+  ApiError {
+    error: "invalid tx".to_string(),
+    code: StatusCode::BAD_REQUEST,
+    message: Some(message),
+    details: Vec::new(),
+  }
+}
+
+/// Lists the validity predicates that rejected the tx, along with their errors
+fn rejection_message(result: &TxResult) -> String {
+  let rejected = result.vps_result.rejected_vps.iter()
+    .map(|address| address.to_string())
+    .collect::<Vec<String>>()
+    .join(", ");
+  let errors = result.vps_result.errors.iter()
+    .map(|(address, error)| format!("{}: {}", address, error))
+    .collect::<Vec<String>>()
+    .join("; ");
+
+  match errors.is_empty() {
+    true => format!("rejected by {}", rejected),
+    false => format!("rejected by {} ({})", rejected, errors),
+  }
+}
+
+/// The events the tx would emit: its IBC events, plus one for each account it would create
+fn tx_events(result: &TxResult) -> Vec<EventInfo> {
+  let ibc_events = result.ibc_events.iter().map(|event| EventInfo {
+    kind: event.event_type.clone(),
+    attributes: event.attributes.iter()
+      .map(|(key, value)| EventAttribute { key: key.clone(), value: value.clone(), index: true })
+      .collect(),
+  });
+  let account_events = result.initialized_accounts.iter().map(|address| EventInfo {
+    kind: "init_account".to_string(),
+    attributes: vec![EventAttribute { key: "address".to_string(), value: address.to_string(), index: true }],
+  });
+
+  ibc_events.chain(account_events).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use base64::encode;
+  use namada_sdk::types::address::{Address, InternalAddress};
+  use serde_json::Value;
+
+  fn request(tx_bytes: Option<&str>, tx: Option<Value>) -> SimulateRequest {
+    SimulateRequest {
+      tx_bytes: tx_bytes.map(str::to_string),
+      tx,
+    }
+  }
+
+  fn rejected_message(request: SimulateRequest) -> String {
+    let error = parse_request(&request).unwrap_err();
+    assert_eq!(error.code, StatusCode::BAD_REQUEST);
+    error.message.unwrap_or_default()
+  }
+
+  #[test]
+  fn only_wrapper_txs_are_simulated() {
+    let tx_bytes = encode(Tx::from_type(TxType::Raw).to_bytes());
+    assert_eq!(rejected_message(request(Some(&tx_bytes), None)), "only wrapper txs can be simulated");
+  }
+
+  #[test]
+  fn tx_bytes_are_required() {
+    assert_eq!(rejected_message(request(None, None)), "tx_bytes is required");
+    assert!(rejected_message(request(None, Some(Value::Null))).starts_with("only tx_bytes is supported"));
+    assert!(rejected_message(request(Some("not base64!"), None)).starts_with("tx_bytes isn't valid base64"));
+    assert!(rejected_message(request(Some(&encode(b"not a tx")), None)).starts_with("tx_bytes isn't a Namada tx"));
+  }
+
+  #[test]
+  fn rejection_lists_vps_and_errors() {
+    let mut result = TxResult::default();
+    let pos = Address::Internal(InternalAddress::PoS);
+    result.vps_result.rejected_vps.insert(pos.clone());
+    assert_eq!(rejection_message(&result), format!("rejected by {}", pos));
+
+    result.vps_result.errors.push((pos.clone(), "invalid bond".to_string()));
+    assert_eq!(rejection_message(&result), format!("rejected by {} ({}: invalid bond)", pos, pos));
+  }
+}
@@ -1,5 +1,6 @@
 use axum::{
-  extract::{Query, State},
+  extract::{Path, Query, State},
+  http::HeaderMap,
   Json,
 };
 use std::{
@@ -7,62 +8,105 @@ use std::{
   time::Duration, ops::Div,
 };
 use namada_sdk::{
-  proof_of_stake::{PosParams, types::ValidatorState},
+  proof_of_stake::{Epoch, PosParams, storage_key as pos_storage, types::ValidatorState},
   rpc,
   // core::ledger::parameters::{storage},
-  types::{token::NATIVE_SCALE, dec::Dec},
+  types::{address::Address, token::NATIVE_SCALE, dec::Dec},
 };
 use namada_sdk::types::parameters::EpochDuration;
 use namada_parameters::storage;
 
-use crate::{app::app_state::AppState, model::staking::CosmosValStatus};
+use crate::{app::{app_state::AppState, response_cache::CacheScope}, model::staking::CosmosValStatus};
 use crate::error::api_error::ApiError;
+use crate::handlers::shared::{cached, epoch_start_time, freshness_headers, query_epoch_at, resolve_address, QueryHeight};
 use crate::model::{
-  staking::{PoolResponse, PoolInfo, ParamsResponse, ValidatorsQueryParams, ValidatorsResponse, ValidatorInfo, ValidatorDescription, ConsensusKeyInfo, CommissionInfo, RatesInfo},
+  staking::{PoolResponse, PoolInfo, ParamsResponse, ValidatorsQueryParams, ValidatorsResponse, ValidatorInfo, ValidatorDescription, ConsensusKeyInfo, CommissionInfo, RatesInfo, CommissionHistoryResponse, CommissionChange},
   shared::{NAM, DEFAULT_TIMESTAMP, PaginationInfo, SuffixedDur},
 };
 
 
-pub async fn pool_handler(State(app_state): State<Arc<AppState>>) 
-  -> Result<Json<PoolResponse>, ApiError> {
+pub async fn pool_handler(height: QueryHeight, State(app_state): State<Arc<AppState>>) 
+  -> Result<(HeaderMap, Json<PoolResponse>), ApiError> {
 
-  let current_epoch = rpc::query_epoch(app_state.get_client()).await?;
-  let bonded_tokens = rpc::get_total_staked_tokens(app_state.get_client(), current_epoch).await?.div(NATIVE_SCALE as u64);
-  
-  //TODO: not_bonded tokens
-  let response = PoolResponse {
-    pool: PoolInfo { not_bonded_tokens: "1000000000".to_string(), bonded_tokens },
-  };
+  // the snapshot only holds the latest state
+  if let (None, Some(snapshot)) = (height.0, app_state.current_snapshot()) {
+    //TODO: not_bonded tokens
+    let response = PoolResponse {
+      pool: PoolInfo { not_bonded_tokens: "1000000000".to_string(), bonded_tokens: snapshot.total_staked.div(NATIVE_SCALE as u64) },
+    };
+    return Ok((freshness_headers(Some(snapshot.freshness())), Json(response)));
+  }
 
-  Ok(Json(response))
+  // PoS keeps the total stake per epoch, so a past height reads the total of the epoch it was in
+  let response = cached(&app_state, "staking_pool", &height.cache_key(), CacheScope::Epoch, || async {
+    let epoch = query_epoch_at(&app_state, height).await?;
+    let bonded_tokens = rpc::get_total_staked_tokens(app_state.get_client(), epoch).await?.div(NATIVE_SCALE as u64);
+
+    //TODO: not_bonded tokens
+    Ok(PoolResponse {
+      pool: PoolInfo { not_bonded_tokens: "1000000000".to_string(), bonded_tokens },
+    })
+  }).await?;
+
+  Ok((height.headers(), Json(response)))
 }
 
 pub async fn params_handler(State(app_state): State<Arc<AppState>>) 
   -> Result<Json<ParamsResponse>, ApiError> {
-  
-  let pos_params: PosParams = rpc::get_pos_params(app_state.get_client()).await?;
-  let epoch_dur: EpochDuration = rpc::query_storage_value(app_state.get_client(), &storage::get_epoch_duration_storage_key()).await?;
-  let unbonding_period = Duration::from(epoch_dur.min_duration) * (pos_params.owned.unbonding_len as u32);
-
-  let response = ParamsResponse {
-    unbonding_time: SuffixedDur(unbonding_period),
-    max_validators: pos_params.owned.max_validator_slots as u32,
-    // TODO: need to find out what these two values mean
-    max_entries: 7,
-    historical_entries: 1000,
-    bond_denom: NAM.to_string(),
-  };
+
+  let response = cached(&app_state, "staking_params", "", CacheScope::Epoch, || async {
+    let pos_params: PosParams = rpc::get_pos_params(app_state.get_client()).await?;
+    let epoch_dur: EpochDuration = rpc::query_storage_value(app_state.get_client(), &storage::get_epoch_duration_storage_key()).await?;
+    let unbonding_period = Duration::from(epoch_dur.min_duration) * (pos_params.owned.unbonding_len as u32);
+
+    Ok(ParamsResponse {
+      unbonding_time: SuffixedDur(unbonding_period),
+      max_validators: pos_params.owned.max_validator_slots as u32,
+      // TODO: need to find out what these two values mean
+      max_entries: 7,
+      historical_entries: 1000,
+      bond_denom: NAM.to_string(),
+    })
+  }).await?;
 
   Ok(Json(response))
 }
 
-pub async fn validators_handler(query: Query<ValidatorsQueryParams>, State(app_state): State<Arc<AppState>>) 
-  -> Result<Json<ValidatorsResponse>, ApiError> {
+pub async fn validators_handler(query: Query<ValidatorsQueryParams>, height: QueryHeight, State(app_state): State<Arc<AppState>>) 
+  -> Result<(HeaderMap, Json<ValidatorsResponse>), ApiError> {
 
   // TODO: pagination support
-  let current_epoch = rpc::query_epoch(app_state.get_client()).await?;
-  let all_vals = rpc::get_all_validators(app_state.get_client(), current_epoch).await?;
+  // state, metadata, commission and stake are all fixed for the epoch, so the list is only rebuilt once per epoch;
+  // the follower's snapshot is used if it has been built, otherwise the validators are queried (and cached) here
   let mut response = ValidatorsResponse::new();
+  // a past height gets the validators (state, commission and stake) of the epoch it was in
+  let snapshot = app_state.current_snapshot().filter(|_| height.0.is_none());
+  response.validators = match &snapshot {
+    Some(snapshot) => snapshot.validators.iter()
+      .filter(|validator| filter_validator_by_status(query.status, validator.status))
+      .cloned()
+      .collect(),
+    None => cached(&app_state, "staking_validators", &format!("{:?}{}", query.status, height.cache_key()), CacheScope::Epoch, || async {
+      let epoch = query_epoch_at(&app_state, height).await?;
+      get_validators(&app_state, epoch, query.status).await
+    }).await?,
+  };
+
+  response.pagination = PaginationInfo {
+      next_key: None,
+      total: Some("1".to_string()), 
+    };
+
+  let mut headers = freshness_headers(snapshot.map(|snapshot| snapshot.freshness()));
+  headers.extend(height.headers());
+
+  Ok((headers, Json(response)))
+}
+
+/// Builds the info of every validator in the given epoch that matches the (Cosmos) status filter
+pub(crate) async fn get_validators(app_state: &AppState, current_epoch: Epoch, status_filter: Option<CosmosValStatus>) -> Result<Vec<ValidatorInfo>, ApiError> {
+  let all_vals = rpc::get_all_validators(app_state.get_client(), current_epoch).await?;
+  let mut validators = Vec::new();
 
   for val in &all_vals {
     // check validator status first
@@ -72,7 +116,7 @@ pub async fn validators_handler(query: Query<ValidatorsQueryParams>, State(app_s
       None => (true, CosmosValStatus::BOND_STATUS_UNBONDED)
     };
 
-    if filter_validator_by_status(query.status, status) {
+    if filter_validator_by_status(status_filter, status) {
       let (metadata, commission_info) = rpc::query_metadata(app_state.get_client(), val, Some(current_epoch)).await?;
       let description: ValidatorDescription = match metadata {
         Some(metadata) => {
@@ -87,15 +131,15 @@ pub async fn validators_handler(query: Query<ValidatorsQueryParams>, State(app_s
         None => ValidatorDescription::empty(val)
       };
 
+      // update_time is filled in by `set_commission_update_times`, only for the chain follower's snapshot
       let commission = match commission_info {
         Some(commission_info) => {
           CommissionInfo {
             commission_rates: RatesInfo {
               rate: commission_info.commission_rate,
-              max_rate: Dec::one(), // no such paramater in Namada
+              max_rate: Dec::one(),
               max_change_rate: commission_info.max_commission_change_per_epoch,
             },
-            //TODO: placeholder... how to query this?
             update_time: DEFAULT_TIMESTAMP.to_string(),
           }
         }
@@ -106,7 +150,7 @@ pub async fn validators_handler(query: Query<ValidatorsQueryParams>, State(app_s
 
       // Contruct response info
       let validator_info = ValidatorInfo {
-        operator_address: val.clone(),
+        operator_address: app_state.address_map.operator_address(val),
         // TODO: Consensus key
         consensus_pubkey: ConsensusKeyInfo {
           at_type: "placeholder".to_string(),
@@ -123,18 +167,86 @@ pub async fn validators_handler(query: Query<ValidatorsQueryParams>, State(app_s
         commission,
         min_self_delegation: "1".to_string(),
       };
-      response.validators.push(validator_info);
+      validators.push(validator_info);
     }
   }
 
-  response.pagination = PaginationInfo {
-      next_key: None,
-      total: Some("1".to_string()), 
+  Ok(validators)
+}
+
+/// Fills in the time each validator's current commission rate took effect
+/// This reads every validator's commission history (and may search for the start heights of epochs), so it's only
+/// done once per epoch, when the chain follower builds its snapshot
+pub(crate) async fn set_commission_update_times(app_state: &AppState, current_epoch: Epoch, validators: &mut [ValidatorInfo]) -> Result<(), ApiError> {
+  for validator in validators.iter_mut() {
+    let address = resolve_address(app_state, &validator.operator_address).await?;
+    let update_time = match get_commission_update_epoch(app_state, &address, current_epoch).await? {
+      Some(epoch) => epoch_start_time(app_state, epoch).await?,
+      None => None,
     };
+    if let Some(update_time) = update_time {
+      validator.commission.update_time = update_time.to_rfc3339();
+    }
+  }
+
+  Ok(())
+}
+
+pub async fn commission_history_handler(Path(validator): Path<String>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<CommissionHistoryResponse>, ApiError> {
+
+  let validator = resolve_address(&app_state, &validator).await?;
+  let current_epoch = rpc::query_epoch(app_state.get_client()).await?;
+  let commission_info = rpc::query_commission_rate(app_state.get_client(), &validator, Some(current_epoch)).await?;
+
+  let mut history = Vec::new();
+  for (epoch, rate) in get_commission_history(&app_state, &validator).await? {
+    history.push(CommissionChange {
+      epoch,
+      rate,
+      update_time: epoch_start_time(&app_state, epoch).await?.map(|time| time.to_rfc3339()),
+    });
+  }
+
+  let response = CommissionHistoryResponse {
+    operator_address: app_state.address_map.operator_address(&validator),
+    max_change_rate: commission_info.map(|info| info.max_commission_change_per_epoch),
+    history,
+  };
 
   Ok(Json(response))
 }
 
+/// Reads the validator's commission rate epoched storage, returning each (epoch, rate) at which the rate changed
+/// Namada only keeps a limited number of past epochs, so the first entry is the oldest rate still in storage,
+/// which isn't necessarily when it was set
+async fn get_commission_history(app_state: &AppState, validator: &Address) -> Result<Vec<(Epoch, Dec)>, ApiError> {
+  let prefix = pos_storage::validator_commission_rate_key(validator);
+  // the prefix also holds the epoched data's 'last update' epoch, which fails to decode as a Dec and is skipped
+  let mut rates: Vec<(Epoch, Dec)> = match rpc::query_storage_prefix::<_, Dec>(app_state.get_namada(), &prefix).await? {
+    Some(entries) => entries
+      .filter_map(|(key, rate)| {
+        let epoch = key.last()?.raw().parse::<u64>().ok()?;
+        Some((Epoch(epoch), rate))
+      })
+      .collect(),
+    None => Vec::new(),
+  };
+  rates.sort_by_key(|(epoch, _)| *epoch);
+  rates.dedup_by(|(_, rate), (_, previous_rate)| rate == previous_rate);
+
+  Ok(rates)
+}
+
+/// Finds the epoch at which the validator's current commission rate took effect
+async fn get_commission_update_epoch(app_state: &AppState, validator: &Address, current_epoch: Epoch) -> Result<Option<Epoch>, ApiError> {
+  let history = get_commission_history(app_state, validator).await?;
+  Ok(history.into_iter()
+    .filter(|(epoch, _)| *epoch <= current_epoch)
+    .last()
+    .map(|(epoch, _)| epoch))
+}
+
 /// Maps Namada validator state to Cosmos validator state
 fn map_status_namada_to_cosmos(namada_status: ValidatorState) -> (bool, CosmosValStatus) {
   match namada_status {
@@ -0,0 +1,126 @@
+use axum::{
+  extract::State,
+  http::{header, StatusCode},
+  response::IntoResponse,
+  Json,
+};
+use std::sync::Arc;
+use namada_sdk::rpc;
+use tendermint::Time;
+use tendermint_rpc::Client;
+use tracing::debug;
+
+use crate::app::{app_state::AppState, chain_follower::SnapshotStatus, metrics, response_cache::CacheStats};
+use crate::error::api_error::ApiError;
+use crate::handlers::uptime::{get_uptime, DEFAULT_UPTIME_WINDOW};
+use crate::model::health::{HealthResponse, NodeSyncInfo};
+
+
+/// Hit/miss counts per cached endpoint, along with the chain position the cache is currently valid for
+pub async fn cache_stats_handler(State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<CacheStats>, ApiError> {
+
+  Ok(Json(app_state.response_cache.stats()))
+}
+
+/// When the chain follower's snapshot was last built, and what it holds
+pub async fn snapshot_status_handler(State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<SnapshotStatus>, ApiError> {
+
+  let snapshot = app_state.current_snapshot();
+  Ok(Json(SnapshotStatus::new(snapshot.as_deref())))
+}
+
+/// Prometheus metrics: requests and latency per route, upstream rpc calls, cache stats and the latest height/epoch,
+/// followed by the chain gauges (validators, governance and missed blocks)
+pub async fn metrics_handler(State(app_state): State<Arc<AppState>>) -> impl IntoResponse {
+  // counted from the rolling signing history, which only fetches the blocks committed since the last sync, so frequent
+  // scrapes don't each walk the window
+  let uptime = match get_uptime(&app_state, DEFAULT_UPTIME_WINDOW).await {
+    Ok(uptime) => Some(uptime),
+    Err(err) => {
+      debug!("leaving missed blocks out of the metrics: {}", err.error);
+      None
+    },
+  };
+
+  let mut body = app_state.metrics.render(&app_state);
+  body.push_str(&metrics::render_chain(app_state.current_snapshot().as_deref(), uptime.as_ref()));
+
+  (
+    [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+    body,
+  )
+}
+
+/// Liveness: the middleware is up and at least one rpc node answers
+pub async fn health_handler(State(app_state): State<Arc<AppState>>) -> (StatusCode, Json<HealthResponse>) {
+  let (node, reasons) = match app_state.get_client().status().await {
+    Ok(status) => (Some(node_sync_info(status)), Vec::new()),
+    Err(err) => (None, vec![format!("rpc node status query failed: {}", err)]),
+  };
+
+  health_response(&app_state, reasons, node, None, "ok", "unavailable")
+}
+
+/// Readiness: the node is synced, its latest block is recent, and the sdk can query the chain
+pub async fn ready_handler(State(app_state): State<Arc<AppState>>) -> (StatusCode, Json<HealthResponse>) {
+  let mut reasons = Vec::new();
+
+  let node = match app_state.get_client().status().await {
+    Ok(status) => Some(node_sync_info(status)),
+    Err(err) => {
+      reasons.push(format!("rpc node status query failed: {}", err));
+      None
+    },
+  };
+  if let Some(node) = &node {
+    if node.catching_up {
+      reasons.push(format!("node is catching up (at height {})", node.latest_block_height));
+    }
+    let max_block_age = app_state.health.max_block_age_secs;
+    if node.block_age_seconds > max_block_age {
+      reasons.push(format!("latest block is {}s old, more than the allowed {}s", node.block_age_seconds, max_block_age));
+    }
+  }
+
+  let epoch = match rpc::query_epoch(app_state.get_client()).await {
+    Ok(epoch) => Some(epoch.to_string()),
+    Err(err) => {
+      reasons.push(format!("epoch query failed: {}", err));
+      None
+    },
+  };
+
+  health_response(&app_state, reasons, node, epoch, "ready", "not_ready")
+}
+
+fn node_sync_info(status: tendermint_rpc::endpoint::status::Response) -> NodeSyncInfo {
+  let latest_block_time = status.sync_info.latest_block_time;
+  NodeSyncInfo {
+    network: status.node_info.network.to_string(),
+    catching_up: status.sync_info.catching_up,
+    latest_block_height: status.sync_info.latest_block_height.to_string(),
+    latest_block_time: latest_block_time.to_rfc3339(),
+    // a block time slightly ahead of the local clock is just clock skew, so it counts as brand new
+    block_age_seconds: Time::now().duration_since(latest_block_time).map(|age| age.as_secs()).unwrap_or_default(),
+  }
+}
+
+/// 200 with `ok_status` when there are no failure reasons, otherwise 503 (so load balancers drop the instance) with `failed_status`
+fn health_response(app_state: &AppState, reasons: Vec<String>, node: Option<NodeSyncInfo>, epoch: Option<String>, ok_status: &str, failed_status: &str)
+  -> (StatusCode, Json<HealthResponse>) {
+
+  let (code, status) = match reasons.is_empty() {
+    true => (StatusCode::OK, ok_status),
+    false => (StatusCode::SERVICE_UNAVAILABLE, failed_status),
+  };
+
+  (code, Json(HealthResponse {
+    status: status.to_string(),
+    reasons,
+    node,
+    epoch,
+    rpc_nodes: app_state.get_client().node_statuses(),
+  }))
+}
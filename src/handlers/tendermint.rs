@@ -1,18 +1,21 @@
-use tendermint_rpc::Client;
+use tendermint_rpc::{Client, endpoint::block};
 use tendermint::block::Height;
 use axum::{
         extract::{Path, State},
+        http::StatusCode,
         Json,
     };
 use std::sync::Arc;
-use namada_sdk::{rpc, types::storage::BlockHeight};
+use tracing::warn;
+use namada_sdk::{proof_of_stake::Epoch, rpc, types::storage::BlockHeight};
 // use hex::FromHex;
 // use base64::{encode_config, STANDARD};
 
-use crate::app::app_state::AppState;
+use crate::app::{app_state::AppState, response_cache::CacheScope};
 use crate::error::api_error::ApiError;
+use crate::handlers::shared::{cached, ensure_consensus_addresses, resolve_address};
 use crate::model::{
-    tendermint::{CosmosAppVersion, NodeInfoResponse, ValidatorSetsResponse, ValidatorInfo, BlockResponse},
+    tendermint::{CosmosAppVersion, NodeInfoResponse, ValidatorSetsResponse, ValidatorInfo, BlockResponse, ConsensusAddressResponse},
     staking::ConsensusKeyInfo,
     shared::PaginationInfo,
 };
@@ -21,7 +24,9 @@ pub async fn latest_block_handler(State(app_state): State<Arc<AppState>>)
   -> Result<Json<BlockResponse>, ApiError> {
 
   let latest_block = app_state.get_client().latest_block().await?;
-  let response = BlockResponse::from(latest_block);
+  let proposer_validator = get_proposer_validator(&app_state, &latest_block).await;
+  let mut response = BlockResponse::from(latest_block);
+  response.block.header.proposer_validator = proposer_validator;
   Ok(Json(response))
 }
 
@@ -29,10 +34,52 @@ pub async fn block_handler(Path(height): Path<u32>, State(app_state): State<Arc<
   -> Result<Json<BlockResponse>, ApiError> {
 
   let block = app_state.get_client().block(Height::from(height)).await?;
-  let response = BlockResponse::from(block);
+  let proposer_validator = get_proposer_validator(&app_state, &block).await;
+  let mut response = BlockResponse::from(block);
+  response.block.header.proposer_validator = proposer_validator;
   Ok(Json(response))
 }
 
+pub async fn consensus_address_handler(Path(address): Path<String>, State(app_state): State<Arc<AppState>>) 
+  -> Result<Json<ConsensusAddressResponse>, ApiError> {
+
+  let validator = resolve_address(&app_state, &address).await?;
+  ensure_consensus_addresses(&app_state).await?;
+
+  // the path may have been a consensus address from a previous key, so prefer it over the validator's current one
+  let consensus_address = match app_state.address_map.parse_consensus_address(&address) {
+    Some(consensus_address) => consensus_address,
+    None => app_state.address_map.consensus_address(&validator).ok_or(ApiError {
+      error: format!("{} is not a validator", validator),
+      code: StatusCode::NOT_FOUND,
+      message: None,
+      details: Vec::new(),
+    })?,
+  };
+
+  let response = ConsensusAddressResponse {
+    valcons_address: app_state.address_map.to_valcons(&validator, &consensus_address),
+    consensus_address,
+    operator_address: app_state.address_map.operator_address(&validator),
+    validator_address: validator,
+  };
+
+  Ok(Json(response))
+}
+
+/// Looks up the validator that proposed a block from the block's (consensus) proposer address
+/// The field is optional, so the block is still served (without it) if the consensus addresses can't be refreshed
+async fn get_proposer_validator(app_state: &AppState, block: &block::Response) -> Option<String> {
+  if let Err(err) = ensure_consensus_addresses(app_state).await {
+    warn!("could not refresh the consensus addresses to find the proposer of block {}: {}", block.block.header.height, err.error);
+    return None;
+  }
+  let proposer_address = hex::encode_upper(block.block.header.proposer_address.as_bytes());
+
+  app_state.address_map.validator_by_consensus(&proposer_address)
+    .map(|validator| app_state.address_map.operator_address(&validator))
+}
+
 pub async fn node_info_handler(State(app_state): State<Arc<AppState>>)
   -> Result<Json<NodeInfoResponse>, ApiError> {
 
@@ -50,34 +97,22 @@ pub async fn node_info_handler(State(app_state): State<Arc<AppState>>)
 pub async fn latest_validator_sets_handler(State(app_state): State<Arc<AppState>>) 
   -> Result<Json<ValidatorSetsResponse>, ApiError> {
 
-  // TODO: pagination support
-  let height = app_state.get_client().latest_block().await?.block.header.height;
-  let current_epoch = rpc::query_epoch(app_state.get_client()).await?;
-  let all_vals = rpc::get_all_validators(app_state.get_client(), current_epoch).await?;
-
-  let mut response = ValidatorSetsResponse {
-    block_height: height.to_string(),
-    validators: Vec::new(),
-    pagination: PaginationInfo {
-        next_key: None,
-        total: Some("1".to_string()),
-    }
-  };
-
-  for val in &all_vals {
-    let stake = rpc::get_validator_stake(app_state.get_client(), current_epoch, val).await?;
-    response.validators.push(ValidatorInfo {
-      address: val.clone(),
-        pub_key: ConsensusKeyInfo {
-          //TODO
-          at_type: "placeholder".to_string(),
-          key: "placeholder".to_string(),
-        },
-      voting_power: stake,
-      //TODO: how to query this?
-      proposer_priority: "234141".to_string(),
+  // the set only changes per epoch, but the response reports the latest height
+  let response = cached(&app_state, "latest_validator_sets", "", CacheScope::Block, || async {
+    // TODO: pagination support
+    let height = app_state.get_client().latest_block().await?.block.header.height;
+    let current_epoch = rpc::query_epoch(app_state.get_client()).await?;
+    let validators = get_validator_set(&app_state, current_epoch, current_epoch).await?;
+
+    Ok(ValidatorSetsResponse {
+      block_height: height.to_string(),
+      validators,
+      pagination: PaginationInfo {
+          next_key: None,
+          total: Some("1".to_string()),
+      }
     })
-  }
+  }).await?;
 
   Ok(Json(response))
 }
@@ -85,30 +120,41 @@ pub async fn latest_validator_sets_handler(State(app_state): State<Arc<AppState>
 pub async fn validator_sets_handler(Path(height): Path<u64>, State(app_state): State<Arc<AppState>>) 
   -> Result<Json<ValidatorSetsResponse>, ApiError> {
 
-  // TODO: pagination support
-  let query_epoch = match rpc::query_epoch_at_height(app_state.get_client(), BlockHeight(height)).await? {
-    Some(epoch) => epoch,
-    None => rpc::query_epoch(app_state.get_client()).await?
-  };
+  let response = cached(&app_state, "validator_sets", &height.to_string(), CacheScope::Epoch, || async {
+    // TODO: pagination support
+    let query_epoch = match rpc::query_epoch_at_height(app_state.get_client(), BlockHeight(height)).await? {
+      Some(epoch) => epoch,
+      None => rpc::query_epoch(app_state.get_client()).await?
+    };
 
-  // TODO: this query returns an empty set... not sure why
-  // let all_vals = rpc::get_all_validators(app_state.get_client(), query_epoch).await?;
-  let current_epoch = rpc::query_epoch(app_state.get_client()).await?; // TEMPORARY
-  let all_vals = rpc::get_all_validators(app_state.get_client(), current_epoch).await?; //TEMPORARY
-
-  let mut response = ValidatorSetsResponse {
-    block_height: height.to_string(),
-    validators: Vec::new(),
-    pagination: PaginationInfo {
-        next_key: None,
-        total: Some("1".to_string()),
-    }
-  };
+    // TODO: this query returns an empty set... not sure why
+    // let all_vals = rpc::get_all_validators(app_state.get_client(), query_epoch).await?;
+    let current_epoch = rpc::query_epoch(app_state.get_client()).await?; // TEMPORARY
+    let validators = get_validator_set(&app_state, current_epoch, query_epoch).await?;
+
+    Ok(ValidatorSetsResponse {
+      block_height: height.to_string(),
+      validators,
+      pagination: PaginationInfo {
+          next_key: None,
+          total: Some("1".to_string()),
+      }
+    })
+  }).await?;
+
+  Ok(Json(response))
+}
 
+/// Lists the validators of `set_epoch` with their voting power (stake) at `stake_epoch`
+async fn get_validator_set(app_state: &AppState, set_epoch: Epoch, stake_epoch: Epoch) -> Result<Vec<ValidatorInfo>, ApiError> {
+  let all_vals = rpc::get_all_validators(app_state.get_client(), set_epoch).await?;
+  ensure_consensus_addresses(app_state).await?;
+
+  let mut validators = Vec::new();
   for val in &all_vals {
-    let stake = rpc::get_validator_stake(app_state.get_client(), query_epoch, val).await?;
-    response.validators.push(ValidatorInfo {
-      address: val.clone(),
+    let stake = rpc::get_validator_stake(app_state.get_client(), stake_epoch, val).await?;
+    validators.push(ValidatorInfo {
+      address: app_state.address_map.consensus_display_address(val),
         pub_key: ConsensusKeyInfo {
           //TODO
           at_type: "placeholder".to_string(),
@@ -120,5 +166,5 @@ pub async fn validator_sets_handler(Path(height): Path<u64>, State(app_state): S
     })
   }
 
-  Ok(Json(response))
+  Ok(validators)
 }
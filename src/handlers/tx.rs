@@ -0,0 +1,111 @@
+use axum::{
+  extract::{Path, Query, State},
+  http::StatusCode,
+  Json,
+};
+use std::sync::Arc;
+
+use crate::app::app_state::AppState;
+use crate::error::api_error::ApiError;
+use crate::handlers::shared::resolve_address;
+use crate::indexer::{self, store::{IndexStore, TxFilter}};
+use crate::model::{
+  shared::PaginationQueryParams,
+  tx::{AccountTxsQueryParams, GetTxResponse, GetTxsEventResponse, TxInfo, TxResponse, TxSearchQueryParams},
+};
+
+
+pub async fn get_tx_handler(Path(hash): Path<String>, State(app_state): State<Arc<AppState>>)
+  -> Result<Json<GetTxResponse>, ApiError> {
+
+  let store = index_store(&app_state)?;
+  let lookup_hash = hash.clone();
+  let tx = indexer::run_blocking(store, move |store| store.get_tx(&lookup_hash)).await?;
+
+  match tx {
+    Some(tx) => Ok(Json(GetTxResponse {
+      tx: TxInfo::from(&tx),
+      tx_response: TxResponse::from(&tx),
+    })),
+    None => Err(ApiError {
+      error: format!("tx not found: {}", hash),
+      code: StatusCode::NOT_FOUND,
+      message: None,
+      details: Vec::new(),
+    }),
+  }
+}
+
+pub async fn search_txs_handler(query: Query<TxSearchQueryParams>, State(app_state): State<Arc<AppState>>)
+  -> Result<Json<GetTxsEventResponse>, ApiError> {
+
+  let conditions = query.events.as_deref().or(query.query.as_deref()).unwrap_or_default();
+  let filter = parse_tx_filter(&app_state, conditions).await?;
+
+  search_txs(&app_state, filter, &query.pagination).await.map(Json)
+}
+
+pub async fn account_txs_handler(Path(address): Path<String>, query: Query<AccountTxsQueryParams>, State(app_state): State<Arc<AppState>>)
+  -> Result<Json<GetTxsEventResponse>, ApiError> {
+
+  let address = resolve_address(&app_state, &address).await?;
+  let filter = TxFilter { address: Some(address.to_string()), ..TxFilter::default() };
+
+  search_txs(&app_state, filter, &query.pagination).await.map(Json)
+}
+
+async fn search_txs(app_state: &AppState, filter: TxFilter, pagination: &PaginationQueryParams) -> Result<GetTxsEventResponse, ApiError> {
+  let store = index_store(app_state)?;
+  let (offset, limit) = pagination.offset_and_limit();
+  let reverse = pagination.reverse.unwrap_or(false);
+  let (txs, total) = indexer::run_blocking(store, move |store| store.search_txs(&filter, offset, limit, reverse)).await?;
+
+  Ok(GetTxsEventResponse {
+    txs: txs.iter().map(TxInfo::from).collect(),
+    tx_responses: txs.iter().map(TxResponse::from).collect(),
+    pagination: PaginationQueryParams::page_info(offset, txs.len(), total),
+    total: total.to_string(),
+  })
+}
+
+/// Parses Cosmos tx search conditions, eg: `message.sender='tnam...' AND tx.height=100`
+/// `tx.height` filters on the block height; any other condition is taken as an address the tx must involve
+async fn parse_tx_filter(app_state: &AppState, conditions: &str) -> Result<TxFilter, ApiError> {
+  let mut filter = TxFilter::default();
+
+  for condition in conditions.split(" AND ").flat_map(|part| part.split(',')).map(str::trim).filter(|part| !part.is_empty()) {
+    let invalid_condition = || ApiError {
+      error: "invalid tx search condition".to_string(),
+      code: StatusCode::BAD_REQUEST,
+      message: Some(format!("invalid condition: {}", condition)),
+      details: Vec::new(),
+    };
+
+    let (key, value) = condition.split_once('=').ok_or_else(invalid_condition)?;
+    let value = value.trim().trim_matches(|c| c == '\'' || c == '"');
+    match key.trim() {
+      "tx.height" => filter.height = Some(value.parse().map_err(|_| invalid_condition())?),
+      _ => filter.address = Some(resolve_address(app_state, value).await?.to_string()),
+    }
+  }
+
+  if filter.height.is_none() && filter.address.is_none() {
+    return Err(ApiError {
+      error: "no tx search conditions".to_string(),
+      code: StatusCode::BAD_REQUEST,
+      message: Some("at least one of the 'events' or 'query' conditions is required".to_string()),
+      details: Vec::new(),
+    });
+  }
+
+  Ok(filter)
+}
+
+fn index_store(app_state: &AppState) -> Result<&Arc<IndexStore>, ApiError> {
+  app_state.index.as_ref().ok_or(ApiError {
+    error: "the tx index is disabled".to_string(),
+    code: StatusCode::NOT_IMPLEMENTED,
+    message: Some("enable [indexer] in the config to query txs".to_string()),
+    details: Vec::new(),
+  })
+}
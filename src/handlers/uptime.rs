@@ -0,0 +1,197 @@
+use axum::{
+  extract::{Path, Query, State},
+  http::StatusCode,
+  Json,
+};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::{
+  collections::BTreeMap,
+  sync::Arc,
+};
+use tendermint::{block::CommitSig, Block};
+use tendermint_rpc::{Client, Paging};
+
+use crate::app::{app_state::AppState, signing_history::{BlockSigning, SIGNING_HISTORY_BLOCKS}};
+use crate::error::api_error::ApiError;
+use crate::handlers::shared::{ensure_consensus_addresses, resolve_address, tendermint_height};
+use crate::model::uptime::{UptimeQueryParams, UptimeResponse, ValidatorUptime, ValidatorUptimeResponse};
+
+pub(crate) const DEFAULT_UPTIME_WINDOW: u64 = 100;
+const MAX_UPTIME_WINDOW: u64 = SIGNING_HISTORY_BLOCKS as u64;
+/// How many blocks are requested from the node at a time
+const BLOCK_FETCH_CONCURRENCY: usize = 16;
+
+#[derive(Default)]
+struct UptimeTally {
+  signed: u64,
+  missed: u64,
+  proposed: u64,
+}
+
+
+pub async fn uptime_handler(query: Query<UptimeQueryParams>, State(app_state): State<Arc<AppState>>)
+  -> Result<Json<UptimeResponse>, ApiError> {
+
+  let window = uptime_window(query.window)?;
+  let response = get_uptime(&app_state, window).await?;
+
+  Ok(Json(response))
+}
+
+pub async fn validator_uptime_handler(Path(validator): Path<String>, query: Query<UptimeQueryParams>, State(app_state): State<Arc<AppState>>)
+  -> Result<Json<ValidatorUptimeResponse>, ApiError> {
+
+  let window = uptime_window(query.window)?;
+  let validator = resolve_address(&app_state, &validator).await?;
+  sync_signing_history(&app_state).await?;
+  ensure_consensus_addresses(&app_state).await?;
+  let blocks = app_state.signing_history.recent(window as usize);
+  let uptime = tally_uptime(&app_state, &blocks);
+
+  // a validator that wasn't in the consensus set during the window has nothing to count
+  let uptime_info = uptime.validators.into_iter()
+    .find(|info| info.validator.as_ref() == Some(&validator))
+    .unwrap_or_else(|| ValidatorUptime {
+      operator_address: Some(app_state.address_map.operator_address(&validator)),
+      consensus_address: app_state.address_map.consensus_address(&validator).unwrap_or_default(),
+      signed: "0".to_string(),
+      missed: "0".to_string(),
+      proposed: "0".to_string(),
+      uptime: 0.0,
+      validator: Some(validator),
+    });
+  let missed_heights = blocks.iter()
+    .filter(|block| block.missed.contains(&uptime_info.consensus_address))
+    .map(|block| block.height.to_string())
+    .collect();
+
+  let response = ValidatorUptimeResponse {
+    from_height: uptime.from_height,
+    to_height: uptime.to_height,
+    window: uptime.window,
+    uptime: uptime_info,
+    missed_heights,
+  };
+
+  Ok(Json(response))
+}
+
+fn uptime_window(window: Option<u64>) -> Result<u64, ApiError> {
+  match window.unwrap_or(DEFAULT_UPTIME_WINDOW) {
+    window @ 1..=MAX_UPTIME_WINDOW => Ok(window),
+    window => Err(ApiError {
+      error: "invalid uptime window".to_string(),
+      code: StatusCode::BAD_REQUEST,
+      message: Some(format!("window must be between 1 and {}, got {}", MAX_UPTIME_WINDOW, window)),
+      details: Vec::new(),
+    }),
+  }
+}
+
+/// Counts the blocks each validator signed, missed and proposed over the last `window` committed blocks
+pub(crate) async fn get_uptime(app_state: &AppState, window: u64) -> Result<UptimeResponse, ApiError> {
+  sync_signing_history(app_state).await?;
+  ensure_consensus_addresses(app_state).await?;
+  let blocks = app_state.signing_history.recent(window as usize);
+
+  Ok(tally_uptime(app_state, &blocks))
+}
+
+fn tally_uptime(app_state: &AppState, blocks: &[Arc<BlockSigning>]) -> UptimeResponse {
+  let mut tallies: BTreeMap<&str, UptimeTally> = BTreeMap::new();
+  for block in blocks {
+    tallies.entry(&block.proposer).or_default().proposed += 1;
+    for consensus_address in &block.signed {
+      tallies.entry(consensus_address).or_default().signed += 1;
+    }
+    for consensus_address in &block.missed {
+      tallies.entry(consensus_address).or_default().missed += 1;
+    }
+  }
+
+  let validators = tallies.into_iter()
+    .map(|(consensus_address, tally)| {
+      let validator = app_state.address_map.validator_by_consensus(consensus_address);
+      let counted = tally.signed + tally.missed;
+      ValidatorUptime {
+        operator_address: validator.as_ref().map(|validator| app_state.address_map.operator_address(validator)),
+        consensus_address: consensus_address.to_string(),
+        signed: tally.signed.to_string(),
+        missed: tally.missed.to_string(),
+        proposed: tally.proposed.to_string(),
+        uptime: if counted == 0 { 0.0 } else { tally.signed as f64 / counted as f64 },
+        validator,
+      }
+    })
+    .collect();
+
+  UptimeResponse {
+    from_height: blocks.first().map(|block| block.height).unwrap_or_default().to_string(),
+    to_height: blocks.last().map(|block| block.height).unwrap_or_default().to_string(),
+    window: blocks.len().to_string(),
+    validators,
+  }
+}
+
+/// Records the signatures of the blocks committed since the last sync, fetching at most `SIGNING_HISTORY_BLOCKS`
+/// Block h's signatures are in the LastCommit of block h+1, so the latest block is only recorded once the next one is in
+pub(crate) async fn sync_signing_history(app_state: &AppState) -> Result<(), ApiError> {
+  let history = &app_state.signing_history;
+  let _syncing = history.lock_sync().await;
+
+  let client = app_state.get_client();
+  let latest_height = client.latest_block().await?.block.header.height.value();
+  let to_height = latest_height.saturating_sub(1);
+  let from_height = history.latest_height()
+    .map(|height| height + 1)
+    .unwrap_or(1)
+    .max(latest_height.saturating_sub(MAX_UPTIME_WINDOW))
+    .max(1);
+  if from_height > to_height {
+    return Ok(());
+  }
+
+  let blocks: Vec<Block> = stream::iter(from_height..=latest_height)
+    .map(|height| async move {
+      Ok::<_, ApiError>(client.block(tendermint_height(height)?).await?.block)
+    })
+    .buffered(BLOCK_FETCH_CONCURRENCY)
+    .try_collect()
+    .await?;
+
+  let mut recorded = Vec::new();
+  for pair in blocks.windows(2) {
+    let (block, next_block) = (&pair[0], &pair[1]);
+    let mut signing = BlockSigning {
+      height: block.header.height.value(),
+      proposer: hex::encode_upper(block.header.proposer_address.as_bytes()),
+      signed: Vec::new(),
+      missed: Vec::new(),
+    };
+
+    // a commit's signatures are in validator set order, and absent signatures don't carry an address,
+    // so each commit is matched up with its validator set (only queried again when the set changes)
+    if let Some(commit) = &next_block.last_commit {
+      let validator_set = match history.validator_set(&block.header.validators_hash) {
+        Some(validator_set) => validator_set,
+        None => {
+          let validators = client.validators(block.header.height, Paging::All).await?.validators;
+          let validator_set = Arc::new(validators.iter().map(|validator| hex::encode_upper(validator.address.as_bytes())).collect::<Vec<String>>());
+          history.set_validator_set(block.header.validators_hash, Arc::clone(&validator_set));
+          validator_set
+        },
+      };
+
+      for (signature, consensus_address) in commit.signatures.iter().zip(validator_set.iter()) {
+        match signature {
+          CommitSig::BlockIdFlagAbsent => signing.missed.push(consensus_address.clone()),
+          CommitSig::BlockIdFlagCommit { .. } | CommitSig::BlockIdFlagNil { .. } => signing.signed.push(consensus_address.clone()),
+        }
+      }
+    }
+    recorded.push(signing);
+  }
+
+  history.extend(recorded);
+  Ok(())
+}
@@ -0,0 +1,273 @@
+use borsh::BorshDeserialize;
+use namada_sdk::{
+  governance::storage::proposal::{InitProposalData, VoteProposalData},
+  proto::{Section, Tx},
+  rpc,
+  types::{
+    address::Address,
+    key::common,
+    storage::BlockHeight,
+    token::Transfer,
+    transaction::{
+      account::UpdateAccount,
+      pos::{Bond, CommissionChange, Redelegation, Withdraw},
+      TxType,
+    },
+  },
+};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use tendermint::{abci::Event, block::{CommitSig, Height}};
+use tendermint_rpc::Client;
+
+use crate::app::rpc_pool::RpcPool;
+
+/// Everything the indexer stores from a single block
+pub struct IndexedBlock {
+  pub height: u64,
+  pub hash: String,
+  pub time: String,
+  pub proposer_address: String, // hex consensus address
+  pub epoch: Option<u64>,
+  pub txs: Vec<IndexedTx>,
+  pub events: Vec<IndexedEvent>,
+  // the height the block's last commit signatures are for
+  pub commit_height: Option<u64>,
+  pub signatures: Vec<IndexedSignature>,
+}
+
+pub struct IndexedTx {
+  pub hash: String,
+  pub index: u32,
+  pub tx_type: String,
+  pub code_tag: Option<String>,
+  pub memo: Option<String>,
+  pub fee_payer: Option<String>,
+  pub fee_amount: Option<String>,
+  pub fee_token: Option<String>,
+  pub gas_limit: Option<String>,
+  pub gas_used: String,
+  pub result_code: u32,
+  pub result_log: String,
+  pub data: Value,
+  // (address, role) of every account the tx involves, eg: (tnam..., "sender")
+  pub accounts: Vec<(String, &'static str)>,
+}
+
+pub struct IndexedEvent {
+  pub tx_hash: Option<String>,
+  pub source: &'static str, // begin_block, tx or end_block
+  pub kind: String,
+  pub attributes: Value,
+}
+
+pub struct IndexedSignature {
+  pub validator_address: String, // hex consensus address
+  pub flag: &'static str,
+}
+
+/// Fetches a block and its results from the node, decoding its Namada txs
+pub async fn fetch_block(client: &RpcPool, height: u64) -> Result<IndexedBlock, tendermint_rpc::Error> {
+  let tm_height = Height::try_from(height).map_err(|err| tendermint_rpc::Error::client_internal(err.to_string()))?;
+  let block = client.block(tm_height).await?.block;
+  let results = client.block_results(tm_height).await?;
+  // the epoch is nice to have, but a block is still worth indexing without it
+  let epoch = rpc::query_epoch_at_height(client, BlockHeight(height)).await.ok().flatten().map(|epoch| epoch.0);
+
+  let mut events = Vec::new();
+  events.extend(index_events(results.begin_block_events.unwrap_or_default(), None, "begin_block"));
+
+  let tx_results = results.txs_results.unwrap_or_default();
+  let mut txs = Vec::new();
+  for (index, tx_bytes) in block.data.iter().enumerate() {
+    let hash = hex::encode_upper(Sha256::digest(tx_bytes));
+    let result = tx_results.get(index);
+    let mut tx = decode_tx(tx_bytes);
+    tx.index = index as u32;
+
+    if let Some(result) = result {
+      tx.gas_used = result.gas_used.to_string();
+      tx.result_code = result.code.value();
+      tx.result_log = result.log.clone();
+      events.extend(index_events(result.events.clone(), Some(hash.clone()), "tx"));
+    }
+    tx.hash = hash;
+    txs.push(tx);
+  }
+
+  events.extend(index_events(results.end_block_events.unwrap_or_default(), None, "end_block"));
+
+  let (commit_height, signatures) = match &block.last_commit {
+    Some(commit) => (Some(commit.height.value()), commit.signatures.iter().filter_map(index_signature).collect()),
+    None => (None, Vec::new()),
+  };
+
+  Ok(IndexedBlock {
+    height,
+    hash: block.header.hash().to_string(),
+    time: block.header.time.to_rfc3339(),
+    proposer_address: hex::encode_upper(block.header.proposer_address.as_bytes()),
+    epoch,
+    txs,
+    events,
+    commit_height,
+    signatures,
+  })
+}
+
+impl Default for IndexedTx {
+  fn default() -> Self {
+    IndexedTx {
+      hash: String::new(),
+      index: 0,
+      tx_type: "unknown".to_string(),
+      code_tag: None,
+      memo: None,
+      fee_payer: None,
+      fee_amount: None,
+      fee_token: None,
+      gas_limit: None,
+      gas_used: String::new(),
+      result_code: 0,
+      result_log: String::new(),
+      data: json!({}),
+      accounts: Vec::new(),
+    }
+  }
+}
+
+/// Decodes what we can from a raw Namada tx: its type, fee, wasm code tag and (for the common txs) data
+fn decode_tx(bytes: &[u8]) -> IndexedTx {
+  let Ok(tx) = Tx::try_from(bytes) else {
+    return IndexedTx::default();
+  };
+
+  let mut indexed = IndexedTx {
+    tx_type: match &tx.header.tx_type {
+      TxType::Raw => "raw",
+      TxType::Wrapper(_) => "wrapper",
+      TxType::Decrypted(_) => "decrypted",
+      TxType::Protocol(_) => "protocol",
+    }.to_string(),
+    code_tag: tx.get_section(tx.code_sechash()).and_then(|section| match section.as_ref() {
+      Section::Code(code) => code.tag.clone(),
+      _ => None,
+    }),
+    memo: tx.memo().and_then(|memo| String::from_utf8(memo).ok()),
+    ..IndexedTx::default()
+  };
+
+  if let TxType::Wrapper(wrapper) = &tx.header.tx_type {
+    let fee_payer = wrapper.fee_payer();
+    indexed.fee_payer = Some(fee_payer.to_string());
+    indexed.fee_amount = Some(wrapper.fee.amount_per_gas_unit.to_string());
+    indexed.fee_token = Some(wrapper.fee.token.to_string());
+    indexed.gas_limit = Some(u64::from(wrapper.gas_limit.clone()).to_string());
+    indexed.accounts.push((fee_payer.to_string(), "fee_payer"));
+  }
+
+  if let Some(data) = tx.data() {
+    let (data, accounts) = decode_tx_data(indexed.code_tag.as_deref(), &data);
+    indexed.data = data;
+    indexed.accounts.extend(accounts);
+  }
+
+  indexed
+}
+
+/// Decodes the data of the txs that explorers care about, based on the tx's wasm code tag, eg: 'tx_transfer.wasm'
+/// Returns the data as json, along with the accounts it involves
+fn decode_tx_data(code_tag: Option<&str>, data: &[u8]) -> (Value, Vec<(String, &'static str)>) {
+  let kind = code_tag.map(|tag| tag.trim_end_matches(".wasm")).unwrap_or_default();
+  let decoded = match kind {
+    "tx_transfer" => Transfer::try_from_slice(data).ok().map(|transfer| (
+      json!({ "source": transfer.source, "target": transfer.target, "token": transfer.token, "amount": transfer.amount.to_string() }),
+      vec![(transfer.source, "sender"), (transfer.target, "recipient")],
+    )),
+    "tx_bond" | "tx_unbond" => Bond::try_from_slice(data).ok().map(|bond| {
+      let delegator = bond.source.clone().unwrap_or_else(|| bond.validator.clone());
+      (
+        json!({ "validator": bond.validator, "delegator": delegator, "amount": bond.amount.to_string_native() }),
+        vec![(delegator, "delegator"), (bond.validator, "validator")],
+      )
+    }),
+    "tx_withdraw" | "tx_claim_rewards" => Withdraw::try_from_slice(data).ok().map(|withdraw| {
+      let delegator = withdraw.source.clone().unwrap_or_else(|| withdraw.validator.clone());
+      (
+        json!({ "validator": withdraw.validator, "delegator": delegator }),
+        vec![(delegator, "delegator"), (withdraw.validator, "validator")],
+      )
+    }),
+    "tx_redelegate" => Redelegation::try_from_slice(data).ok().map(|redelegation| (
+      json!({
+        "src_validator": redelegation.src_validator,
+        "dest_validator": redelegation.dest_validator,
+        "delegator": redelegation.owner,
+        "amount": redelegation.amount.to_string_native(),
+      }),
+      vec![
+        (redelegation.owner, "delegator"),
+        (redelegation.src_validator, "validator"),
+        (redelegation.dest_validator, "validator"),
+      ],
+    )),
+    "tx_change_validator_commission" => CommissionChange::try_from_slice(data).ok().map(|change| (
+      json!({ "validator": change.validator, "new_rate": change.new_rate }),
+      vec![(change.validator, "validator")],
+    )),
+    "tx_vote_proposal" => VoteProposalData::try_from_slice(data).ok().map(|vote| (
+      json!({ "proposal_id": vote.id, "vote": format!("{:?}", vote.vote), "voter": vote.voter }),
+      vec![(vote.voter, "voter")],
+    )),
+    "tx_init_proposal" => InitProposalData::try_from_slice(data).ok().map(|proposal| (
+      json!({
+        "author": proposal.author,
+        "voting_start_epoch": proposal.voting_start_epoch,
+        "voting_end_epoch": proposal.voting_end_epoch,
+        "grace_epoch": proposal.grace_epoch,
+      }),
+      vec![(proposal.author, "proposer")],
+    )),
+    "tx_reveal_pk" => common::PublicKey::try_from_slice(data).ok().map(|public_key| {
+      let address = Address::from(&public_key);
+      (json!({ "public_key": public_key.to_string(), "address": address }), vec![(address, "signer")])
+    }),
+    "tx_update_account" => UpdateAccount::try_from_slice(data).ok().map(|update| (
+      json!({ "address": update.addr, "threshold": update.threshold }),
+      vec![(update.addr, "signer")],
+    )),
+    _ => None,
+  };
+
+  match decoded {
+    Some((data, accounts)) => (data, accounts.into_iter().map(|(address, role)| (address.to_string(), role)).collect()),
+    None => (json!({}), Vec::new()),
+  }
+}
+
+fn index_events(events: Vec<Event>, tx_hash: Option<String>, source: &'static str) -> Vec<IndexedEvent> {
+  events.into_iter()
+    .map(|event| IndexedEvent {
+      tx_hash: tx_hash.clone(),
+      source,
+      kind: event.kind,
+      attributes: Value::Object(event.attributes.into_iter()
+        .map(|attribute| (attribute.key, Value::String(attribute.value)))
+        .collect::<Map<String, Value>>()),
+    })
+    .collect()
+}
+
+fn index_signature(signature: &CommitSig) -> Option<IndexedSignature> {
+  match signature {
+    CommitSig::BlockIdFlagAbsent => None,
+    CommitSig::BlockIdFlagCommit { validator_address, .. } => Some(IndexedSignature {
+      validator_address: hex::encode_upper(validator_address.as_bytes()),
+      flag: "commit",
+    }),
+    CommitSig::BlockIdFlagNil { validator_address, .. } => Some(IndexedSignature {
+      validator_address: hex::encode_upper(validator_address.as_bytes()),
+      flag: "nil",
+    }),
+  }
+}
@@ -0,0 +1,151 @@
+pub mod ingest;
+pub mod store;
+
+use std::{
+  sync::Arc,
+  time::Duration,
+};
+use namada_sdk::rpc;
+use tendermint_rpc::Client;
+use tracing::{debug, error, info, warn};
+
+use crate::app::{app_state::AppState, config::ConfigError, rpc_pool::RpcPool};
+use crate::indexer::store::IndexStore;
+
+/// How long to wait before retrying after the node or the store failed
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+/// Attempts at indexing a block before it's skipped and left to the backfill command, so one bad block doesn't
+/// stop new blocks from being indexed
+const MAX_BLOCK_ATTEMPTS: u32 = 10;
+
+/// Indexes new blocks in the background, starting after the last indexed block
+/// A new index starts at `start_height` if set, otherwise at the latest block; older blocks are left to `backfill`
+pub fn spawn(app_state: Arc<AppState>, store: Arc<IndexStore>, start_height: Option<u64>, poll_interval: Duration) {
+  tokio::spawn(async move {
+    let last_indexed_height = match store.last_indexed_height() {
+      Ok(height) => height,
+      Err(err) => {
+        warn!("could not read the index, not indexing new blocks: {}", err);
+        return;
+      },
+    };
+    let mut next_height = loop {
+      match first_height(app_state.get_client(), last_indexed_height, start_height).await {
+        Ok(height) => break height,
+        Err(err) => {
+          warn!("could not query the node's available blocks to start indexing: {}", err);
+          tokio::time::sleep(RETRY_DELAY).await;
+        },
+      }
+    };
+    info!("indexing blocks from height {}", next_height);
+
+    let mut attempts = 0;
+    loop {
+      let latest_height = match rpc::query_block(app_state.get_client()).await {
+        Ok(Some(block)) => block.height.0,
+        Ok(None) => 0,
+        Err(err) => {
+          warn!("could not query the latest block to index: {}", err);
+          tokio::time::sleep(RETRY_DELAY).await;
+          continue;
+        },
+      };
+
+      while next_height <= latest_height {
+        match index_block(app_state.get_client(), &store, next_height).await {
+          Ok(()) => {
+            next_height += 1;
+            attempts = 0;
+          },
+          Err(err) if attempts + 1 >= MAX_BLOCK_ATTEMPTS => {
+            error!("skipping block {} after {} failed attempts, it can be indexed later with the backfill command: {}", next_height, MAX_BLOCK_ATTEMPTS, err);
+            next_height += 1;
+            attempts = 0;
+          },
+          Err(err) => {
+            attempts += 1;
+            warn!("could not index block {} (attempt {} of {}): {}", next_height, attempts, MAX_BLOCK_ATTEMPTS, err);
+            tokio::time::sleep(RETRY_DELAY).await;
+            break;
+          },
+        }
+      }
+
+      tokio::time::sleep(poll_interval).await;
+    }
+  });
+}
+
+/// The height indexing resumes at: after the last indexed block, else `start_height`, else the latest block
+/// Blocks the node has already pruned are skipped, since they can't be fetched from it
+async fn first_height(client: &RpcPool, last_indexed_height: Option<u64>, start_height: Option<u64>) -> Result<u64, String> {
+  let sync_info = client.status().await.map_err(|err| err.to_string())?.sync_info;
+  let earliest_height = sync_info.earliest_block_height.value().max(1);
+  let height = match (last_indexed_height, start_height) {
+    (Some(height), _) => height + 1,
+    (None, Some(height)) => height,
+    (None, None) => return Ok(sync_info.latest_block_height.value().max(1)),
+  };
+
+  if height < earliest_height {
+    warn!("blocks {} to {} have been pruned from the node and won't be indexed", height, earliest_height - 1);
+  }
+  Ok(height.max(earliest_height))
+}
+
+/// Indexes the blocks from `from_height` (or the node's earliest block) to `to_height` (or the latest block), skipping
+/// blocks already in the index
+pub async fn backfill(client: &RpcPool, store: Arc<IndexStore>, from_height: Option<u64>, to_height: Option<u64>) -> Result<(), ConfigError> {
+  let from_height = match from_height {
+    Some(height) => height,
+    None => client.status().await
+      .map_err(|err| ConfigError::Startup(format!("could not query the node's earliest block: {}", err)))?
+      .sync_info.earliest_block_height.value()
+      .max(1),
+  };
+  let to_height = match to_height {
+    Some(height) => height,
+    None => rpc::query_block(client).await
+      .map_err(|err| ConfigError::Startup(format!("could not query the latest block: {}", err)))?
+      .map(|block| block.height.0)
+      .unwrap_or(0),
+  };
+  info!("backfilling blocks {} to {}", from_height, to_height);
+
+  let mut indexed = 0;
+  for height in from_height..=to_height {
+    let already_indexed = run_blocking(&store, move |store| store.is_indexed(height)).await
+      .map_err(|err| ConfigError::Startup(format!("could not read the index: {}", err)))?;
+    if already_indexed {
+      continue;
+    }
+
+    index_block(client, &store, height).await
+      .map_err(|err| ConfigError::Startup(format!("could not index block {}: {}", height, err)))?;
+    indexed += 1;
+    if indexed % 1000 == 0 {
+      info!("backfilled up to block {}", height);
+    }
+  }
+
+  info!("backfill complete, indexed {} blocks", indexed);
+  Ok(())
+}
+
+async fn index_block(client: &RpcPool, store: &Arc<IndexStore>, height: u64) -> Result<(), String> {
+  let block = ingest::fetch_block(client, height).await.map_err(|err| err.to_string())?;
+  debug!("indexing block {} with {} txs", height, block.txs.len());
+  run_blocking(store, move |store| store.insert_block(&block)).await.map_err(|err| err.to_string())
+}
+
+/// Runs a (blocking) store operation off of the async runtime
+pub async fn run_blocking<T, F>(store: &Arc<IndexStore>, operation: F) -> rusqlite::Result<T>
+where
+  T: Send + 'static,
+  F: FnOnce(&IndexStore) -> rusqlite::Result<T> + Send + 'static,
+{
+  let store = Arc::clone(store);
+  tokio::task::spawn_blocking(move || operation(&store)).await
+    .map_err(|err| rusqlite::Error::ToSqlConversionFailure(Box::new(err)))?
+}
@@ -0,0 +1,278 @@
+use std::{
+  path::Path,
+  sync::Mutex,
+};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::indexer::ingest::IndexedBlock;
+
+const SCHEMA: &str = "
+  CREATE TABLE IF NOT EXISTS blocks (
+    height INTEGER PRIMARY KEY,
+    hash TEXT NOT NULL,
+    time TEXT NOT NULL,
+    proposer_address TEXT NOT NULL,
+    epoch INTEGER,
+    num_txs INTEGER NOT NULL
+  );
+
+  CREATE TABLE IF NOT EXISTS txs (
+    hash TEXT PRIMARY KEY,
+    height INTEGER NOT NULL,
+    tx_index INTEGER NOT NULL,
+    tx_type TEXT NOT NULL,
+    code_tag TEXT,
+    memo TEXT,
+    fee_payer TEXT,
+    fee_amount TEXT,
+    fee_token TEXT,
+    gas_limit TEXT,
+    gas_used TEXT NOT NULL,
+    result_code INTEGER NOT NULL,
+    result_log TEXT NOT NULL,
+    data TEXT NOT NULL
+  );
+  CREATE INDEX IF NOT EXISTS txs_height ON txs (height, tx_index);
+
+  CREATE TABLE IF NOT EXISTS tx_accounts (
+    tx_hash TEXT NOT NULL,
+    address TEXT NOT NULL,
+    role TEXT NOT NULL,
+    height INTEGER NOT NULL,
+    tx_index INTEGER NOT NULL,
+    PRIMARY KEY (tx_hash, address, role)
+  );
+  CREATE INDEX IF NOT EXISTS tx_accounts_address ON tx_accounts (address, height, tx_index);
+
+  CREATE TABLE IF NOT EXISTS events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    height INTEGER NOT NULL,
+    tx_hash TEXT,
+    source TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    attributes TEXT NOT NULL
+  );
+  CREATE INDEX IF NOT EXISTS events_height ON events (height);
+  CREATE INDEX IF NOT EXISTS events_tx_hash ON events (tx_hash);
+
+  CREATE TABLE IF NOT EXISTS signatures (
+    height INTEGER NOT NULL,
+    validator_address TEXT NOT NULL,
+    flag TEXT NOT NULL,
+    PRIMARY KEY (height, validator_address)
+  );
+";
+
+/// A tx as stored in the index, along with the time of its block and the events it emitted
+pub struct StoredTx {
+  pub hash: String,
+  pub height: u64,
+  pub index: u32,
+  pub time: String,
+  pub tx_type: String,
+  pub code_tag: Option<String>,
+  pub memo: Option<String>,
+  pub fee_payer: Option<String>,
+  pub fee_amount: Option<String>,
+  pub fee_token: Option<String>,
+  pub gas_limit: Option<String>,
+  pub gas_used: String,
+  pub result_code: u32,
+  pub result_log: String,
+  // json of the decoded tx data
+  pub data: String,
+  pub events: Vec<StoredEvent>,
+}
+
+pub struct StoredEvent {
+  pub kind: String,
+  // json object of the event attributes
+  pub attributes: String,
+}
+
+/// Which txs a search should match; all of the set fields must match
+#[derive(Default)]
+pub struct TxFilter {
+  pub height: Option<u64>,
+  pub address: Option<String>,
+}
+
+/// Sqlite store of indexed blocks, txs, events and validator signatures
+/// rusqlite is blocking, so callers on the async runtime should go through `spawn_blocking`
+pub struct IndexStore {
+  connection: Mutex<Connection>,
+}
+
+impl IndexStore {
+  pub fn open(path: &Path) -> rusqlite::Result<Self> {
+    let connection = Connection::open(path)?;
+    // let the api read while the indexer writes
+    connection.pragma_update(None, "journal_mode", "WAL")?;
+    connection.pragma_update(None, "synchronous", "NORMAL")?;
+    connection.execute_batch(SCHEMA)?;
+
+    Ok(IndexStore { connection: Mutex::new(connection) })
+  }
+
+  pub fn last_indexed_height(&self) -> rusqlite::Result<Option<u64>> {
+    let connection = self.connection.lock().unwrap();
+    connection.query_row("SELECT MAX(height) FROM blocks", [], |row| row.get::<_, Option<u64>>(0))
+  }
+
+  pub fn is_indexed(&self, height: u64) -> rusqlite::Result<bool> {
+    let connection = self.connection.lock().unwrap();
+    connection.query_row("SELECT EXISTS(SELECT 1 FROM blocks WHERE height = ?1)", [height], |row| row.get(0))
+  }
+
+  /// Writes everything indexed from a block in a single transaction, replacing anything previously indexed at that height
+  pub fn insert_block(&self, block: &IndexedBlock) -> rusqlite::Result<()> {
+    let mut connection = self.connection.lock().unwrap();
+    let transaction = connection.transaction()?;
+
+    for table in ["blocks", "txs", "tx_accounts", "events"] {
+      transaction.execute(&format!("DELETE FROM {} WHERE height = ?1", table), [block.height])?;
+    }
+
+    transaction.execute(
+      "INSERT INTO blocks (height, hash, time, proposer_address, epoch, num_txs) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+      params![block.height, block.hash, block.time, block.proposer_address, block.epoch, block.txs.len()],
+    )?;
+
+    for tx in &block.txs {
+      transaction.execute(
+        "INSERT OR REPLACE INTO txs (hash, height, tx_index, tx_type, code_tag, memo, fee_payer, fee_amount, fee_token, gas_limit, gas_used, result_code, result_log, data)
+          VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        params![
+          tx.hash, block.height, tx.index, tx.tx_type, tx.code_tag, tx.memo, tx.fee_payer, tx.fee_amount, tx.fee_token,
+          tx.gas_limit, tx.gas_used, tx.result_code, tx.result_log, tx.data.to_string(),
+        ],
+      )?;
+      for (address, role) in &tx.accounts {
+        transaction.execute(
+          "INSERT OR IGNORE INTO tx_accounts (tx_hash, address, role, height, tx_index) VALUES (?1, ?2, ?3, ?4, ?5)",
+          params![tx.hash, address, role, block.height, tx.index],
+        )?;
+      }
+    }
+
+    for event in &block.events {
+      transaction.execute(
+        "INSERT INTO events (height, tx_hash, source, kind, attributes) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![block.height, event.tx_hash, event.source, event.kind, event.attributes.to_string()],
+      )?;
+    }
+
+    // signatures in a block's last commit are for the previous height
+    if let Some(commit_height) = block.commit_height {
+      transaction.execute("DELETE FROM signatures WHERE height = ?1", [commit_height])?;
+      for signature in &block.signatures {
+        transaction.execute(
+          "INSERT OR REPLACE INTO signatures (height, validator_address, flag) VALUES (?1, ?2, ?3)",
+          params![commit_height, signature.validator_address, signature.flag],
+        )?;
+      }
+    }
+
+    transaction.commit()
+  }
+
+  pub fn get_tx(&self, hash: &str) -> rusqlite::Result<Option<StoredTx>> {
+    let connection = self.connection.lock().unwrap();
+    let tx = connection.query_row(
+      &format!("{} WHERE txs.hash = ?1", SELECT_TXS),
+      [hash.to_uppercase()],
+      stored_tx_from_row,
+    ).optional()?;
+
+    match tx {
+      Some(mut tx) => {
+        tx.events = tx_events(&connection, &tx.hash)?;
+        Ok(Some(tx))
+      },
+      None => Ok(None),
+    }
+  }
+
+  /// Returns the requested page of txs matching the filter, ordered by height (oldest first unless `reverse`), and the total number of matches
+  pub fn search_txs(&self, filter: &TxFilter, offset: usize, limit: usize, reverse: bool) -> rusqlite::Result<(Vec<StoredTx>, usize)> {
+    let connection = self.connection.lock().unwrap();
+
+    let mut conditions = Vec::new();
+    if filter.height.is_some() {
+      conditions.push("txs.height = :height");
+    }
+    if filter.address.is_some() {
+      conditions.push("txs.hash IN (SELECT tx_hash FROM tx_accounts WHERE address = :address)");
+    }
+    let where_clause = match conditions.is_empty() {
+      true => String::new(),
+      false => format!("WHERE {}", conditions.join(" AND ")),
+    };
+    let order = if reverse { "DESC" } else { "ASC" };
+
+    let height = filter.height.map(|height| height as i64);
+    let mut named_params: Vec<(&str, &dyn rusqlite::ToSql)> = Vec::new();
+    if let Some(height) = &height {
+      named_params.push((":height", height));
+    }
+    if let Some(address) = &filter.address {
+      named_params.push((":address", address));
+    }
+
+    let total: usize = connection.query_row(
+      &format!("SELECT COUNT(*) FROM txs {}", where_clause),
+      named_params.as_slice(),
+      |row| row.get(0),
+    )?;
+
+    let (limit, offset) = (limit as i64, offset as i64);
+    named_params.push((":limit", &limit));
+    named_params.push((":offset", &offset));
+    let mut statement = connection.prepare(&format!(
+      "{} {} ORDER BY txs.height {order}, txs.tx_index {order} LIMIT :limit OFFSET :offset",
+      SELECT_TXS, where_clause, order = order,
+    ))?;
+    let mut txs = statement.query_map(named_params.as_slice(), stored_tx_from_row)?
+      .collect::<rusqlite::Result<Vec<StoredTx>>>()?;
+
+    for tx in &mut txs {
+      tx.events = tx_events(&connection, &tx.hash)?;
+    }
+
+    Ok((txs, total))
+  }
+}
+
+const SELECT_TXS: &str = "
+  SELECT txs.hash, txs.height, txs.tx_index, blocks.time, txs.tx_type, txs.code_tag, txs.memo, txs.fee_payer, txs.fee_amount,
+    txs.fee_token, txs.gas_limit, txs.gas_used, txs.result_code, txs.result_log, txs.data
+  FROM txs JOIN blocks ON blocks.height = txs.height";
+
+fn stored_tx_from_row(row: &Row) -> rusqlite::Result<StoredTx> {
+  Ok(StoredTx {
+    hash: row.get(0)?,
+    height: row.get(1)?,
+    index: row.get(2)?,
+    time: row.get(3)?,
+    tx_type: row.get(4)?,
+    code_tag: row.get(5)?,
+    memo: row.get(6)?,
+    fee_payer: row.get(7)?,
+    fee_amount: row.get(8)?,
+    fee_token: row.get(9)?,
+    gas_limit: row.get(10)?,
+    gas_used: row.get(11)?,
+    result_code: row.get(12)?,
+    result_log: row.get(13)?,
+    data: row.get(14)?,
+    events: Vec::new(),
+  })
+}
+
+fn tx_events(connection: &Connection, tx_hash: &str) -> rusqlite::Result<Vec<StoredEvent>> {
+  let mut statement = connection.prepare("SELECT kind, attributes FROM events WHERE tx_hash = ?1 ORDER BY id")?;
+  let events = statement.query_map([tx_hash], |row| Ok(StoredEvent { kind: row.get(0)?, attributes: row.get(1)? }))?
+    .collect::<rusqlite::Result<Vec<StoredEvent>>>()?;
+
+  Ok(events)
+}
@@ -1,4 +1,6 @@
 pub mod app;
 pub mod handlers;
 pub mod model;
-pub mod error;
\ No newline at end of file
+pub mod error;
+#[cfg(feature = "indexer")]
+pub mod indexer;
\ No newline at end of file
@@ -1,66 +1,192 @@
 use tokio::net::TcpListener;
-use tower_http::cors::CorsLayer;
+use tower_http::cors::{AllowOrigin, CorsLayer};
 use axum::{
-        routing::get,
+        middleware,
+        routing::{get, post},
         Router,
-        http::{header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE}, HeaderValue, Method},
+        http::{header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE}, HeaderName, Method},
     };
-use tendermint_rpc::Url;
+use clap::Parser;
 use std::{
+    process::ExitCode,
     sync::Arc,
-    str::FromStr,
 };
 use dotenv::dotenv;
+use tracing::{error, info};
+use tracing_subscriber::EnvFilter;
+#[cfg(feature = "indexer")]
+use std::time::Duration;
 
+#[cfg(feature = "indexer")]
+use namada_ping_middleware::{app::rpc_pool::RpcPool, handlers::tx, indexer};
 use namada_ping_middleware::{
-    app::app_state,
-    handlers::{bank, distribution, gov, mint, slashing, staking, tendermint},
+    app::{app_state, chain_follower::ChainFollower, metrics, config::{AppConfig, Cli, Command, ConfigError, ConfigOverrides}},
+    error::api_error,
+    handlers::{auth, bank, distribution, fallback, gov, gov_v1, ibc, mint, pgf, shared, simulate, slashing, staking, status, tendermint, uptime},
 };
 
 
 #[tokio::main]
-async fn main() {
+async fn main() -> ExitCode {
+    // .env values are picked up as environment overrides, eg: RPC="http://localhost:26657"
     dotenv().ok();
+    let cli = Cli::parse();
 
-    let listener: TcpListener = TcpListener::bind("0.0.0.0:1317").await.expect("Could not bind to listen address.");
+    let result = match cli.command.unwrap_or_else(|| Command::Serve(ConfigOverrides::default())) {
+        Command::Serve(overrides) => match AppConfig::load(cli.config.as_ref(), overrides) {
+            Ok(config) => serve(config).await,
+            Err(err) => Err(err),
+        },
+        Command::CheckConfig(overrides) => AppConfig::load(cli.config.as_ref(), overrides).map(|config| {
+            println!("{}", toml::to_string_pretty(&config).unwrap_or_default());
+        }),
+        Command::DefaultConfig => {
+            println!("{}", toml::to_string_pretty(&AppConfig::default()).unwrap_or_default());
+            Ok(())
+        },
+        #[cfg(feature = "indexer")]
+        Command::Backfill(args) => match AppConfig::load(cli.config.as_ref(), args.overrides) {
+            Ok(config) => backfill(config, args.from, args.to).await,
+            Err(err) => Err(err),
+        },
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            // logging may not be set up yet if the config was invalid
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        },
+    }
+}
+
+fn init_logging(config: &AppConfig) {
+    // the level was checked when the config was validated
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_new(&config.log.level).unwrap_or_else(|_| EnvFilter::new("info")))
+        .init();
+}
+
+#[cfg(feature = "indexer")]
+async fn backfill(config: AppConfig, from: Option<u64>, to: Option<u64>) -> Result<(), ConfigError> {
+    init_logging(&config);
+
+    let rpc_pool = RpcPool::new(&config.rpc, config.rpc_urls())?;
+    rpc_pool.check_health().await;
+    let store = indexer::store::IndexStore::open(&config.indexer.path)
+        .map_err(|err| ConfigError::Startup(format!("could not open index {}: {}", config.indexer.path.display(), err)))?;
+
+    indexer::backfill(&rpc_pool, Arc::new(store), from.or(config.indexer.start_height), to).await
+}
+
+async fn serve(config: AppConfig) -> Result<(), ConfigError> {
+    init_logging(&config);
+
+    let listen_address = config.listen_address();
+    let listener: TcpListener = TcpListener::bind(listen_address).await
+        .map_err(|err| ConfigError::Startup(format!("could not bind to {}: {}", listen_address, err)))?;
+
+    let allow_origin = match config.cors_origins() {
+        Some(origins) => AllowOrigin::list(origins),
+        None => AllowOrigin::any(),
+    };
+    // browsers need the height header allowed to send it, and exposed to read the echoed height
+    let height_header = HeaderName::from_static(shared::BLOCK_HEIGHT_HEADER);
     let cors = CorsLayer::new()
-        .allow_origin("*".parse::<HeaderValue>().unwrap())
-        .allow_methods([Method::GET])
+        .allow_origin(allow_origin)
+        .allow_methods([Method::GET, Method::POST])
         // .allow_credentials(true)
-        .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE]);
-
-    // eg: RPC="http://localhost:26657"
-    let rpc: &str = &std::env::var("RPC").expect("RPC must be set");
-    let url = Url::from_str(rpc).expect("Invalid RPC address");
+        .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE, height_header.clone()])
+        .expose_headers([height_header]);
 
-    let app_state = Arc::new(app_state::AppState::new(url).await);
+    let app_state = Arc::new(app_state::AppState::new(&config).await?);
+    ChainFollower::spawn(app_state.clone(), &config);
+    #[cfg(feature = "indexer")]
+    if let Some(store) = &app_state.index {
+        indexer::spawn(app_state.clone(), store.clone(), config.indexer.start_height, Duration::from_millis(config.indexer.poll_interval_ms));
+    }
 
-    let app: Router = Router::new()
+    let router = Router::new()
         // .route("/cosmos/staking/v1beta1/validators/:address", get(cosmos_handler::validators_handler))
-        .route("/cosmos/bank/v1beta1/supply", get(bank::supply_handler))
-        .route("/cosmos/bank/v1beta1/supply/nam", get(bank::supply_denom_handler))
-        .route("/cosmos/distribution/v1beta1/community_pool", get(distribution::community_pool_handler))
+        .route("/cosmos/auth/v1beta1/accounts/:address", get(auth::account_handler))
+        .route("/cosmos/auth/v1beta1/params", get(auth::auth_params_handler))
+        .route("/cosmos/distribution/v1beta1/delegators/:delegator/rewards", get(distribution::delegator_rewards_handler))
+        .route("/cosmos/distribution/v1beta1/delegators/:delegator/rewards/:validator", get(distribution::delegation_rewards_handler))
         .route("/cosmos/distribution/v1beta1/params", get(distribution::distribution_params_handler))
+        .route("/cosmos/distribution/v1beta1/validators/:validator", get(distribution::validator_distribution_info_handler))
+        .route("/cosmos/distribution/v1beta1/validators/:validator/commission", get(distribution::validator_commission_handler))
+        .route("/cosmos/distribution/v1beta1/validators/:validator/outstanding_rewards", get(distribution::validator_outstanding_rewards_handler))
         .route("/cosmos/gov/v1beta1/params/deposit", get(gov::params_deposit_handler))
         .route("/cosmos/gov/v1beta1/params/tallying", get(gov::params_tallying_handler))
         .route("/cosmos/gov/v1beta1/params/voting", get(gov::params_voting_handler))
-        .route("/cosmos/gov/v1beta1/proposals", get(gov::all_proposals_handler))
-        .route("/cosmos/gov/v1beta1/proposals/:id", get(gov::single_proposal_handler))
-        .route("/cosmos/gov/v1beta1/proposals/:id/tally", get(gov::proposal_tally_handler))
+        .route("/cosmos/gov/v1/params/:type", get(gov_v1::params_handler))
         .route("/cosmos/mint/v1beta1/inflation", get(mint::inflation_handler))
         .route("/cosmos/slashing/v1beta1/params", get(slashing::slashing_params_handler))
         .route("/cosmos/slashing/v1beta1/signing_infos", get(slashing::signing_infos_handler))
         .route("/cosmos/staking/v1beta1/params", get(staking::params_handler))
-        .route("/cosmos/staking/v1beta1/pool", get(staking::pool_handler))
-        .route("/cosmos/staking/v1beta1/validators", get(staking::validators_handler))
         .route("/cosmos/base/tendermint/v1beta1/blocks/latest", get(tendermint::latest_block_handler))
         .route("/cosmos/base/tendermint/v1beta1/blocks/:height", get(tendermint::block_handler))
         .route("/cosmos/base/tendermint/v1beta1/node_info", get(tendermint::node_info_handler))
         .route("/cosmos/base/tendermint/v1beta1/validatorsets/latest", get(tendermint::latest_validator_sets_handler))
         .route("/cosmos/base/tendermint/v1beta1/validatorsets/:height", get(tendermint::validator_sets_handler))
+        .route("/namada/validators/:validator/commission_history", get(staking::commission_history_handler))
+        .route("/namada/validators/:validator/uptime", get(uptime::validator_uptime_handler))
+        .route("/ibc/apps/transfer/v1/denom_traces", get(ibc::denom_traces_handler))
+        .route("/ibc/apps/transfer/v1/denom_traces/:hash", get(ibc::denom_trace_handler))
+        .route("/ibc/core/channel/v1/channels", get(ibc::channels_handler))
+        .route("/ibc/core/channel/v1/channels/:channel/ports/:port", get(ibc::channel_handler))
+        .route("/ibc/core/channel/v1/channels/:channel/ports/:port/packet_acknowledgements", get(ibc::packet_acknowledgements_handler))
+        .route("/ibc/core/channel/v1/channels/:channel/ports/:port/packet_commitments", get(ibc::packet_commitments_handler))
+        .route("/ibc/core/channel/v1/channels/:channel/ports/:port/packet_commitments/:sequences/unreceived_acks", get(ibc::unreceived_acks_handler))
+        .route("/ibc/core/channel/v1/channels/:channel/ports/:port/packet_commitments/:sequences/unreceived_packets", get(ibc::unreceived_packets_handler))
+        .route("/ibc/core/client/v1/client_states", get(ibc::client_states_handler))
+        .route("/ibc/core/connection/v1/connections", get(ibc::connections_handler))
+        .route("/namada/consensus_address/:address", get(tendermint::consensus_address_handler))
+        .route("/namada/uptime", get(uptime::uptime_handler))
+        .route("/namada/pgf/stewards", get(pgf::stewards_handler))
+        .route("/namada/pgf/fundings", get(pgf::fundings_handler));
+    #[cfg(feature = "indexer")]
+    let router = router
+        .route("/cosmos/tx/v1beta1/txs", get(tx::search_txs_handler))
+        .route("/cosmos/tx/v1beta1/txs/:hash", get(tx::get_tx_handler))
+        .route("/namada/accounts/:address/txs", get(tx::account_txs_handler));
+    // the endpoints that can answer for a past height (`x-cosmos-block-height` or `?height=`); the others refuse one
+    let historical_router = Router::new()
+        .route("/cosmos/bank/v1beta1/balances/:address", get(bank::balances_handler))
+        .route("/cosmos/bank/v1beta1/supply", get(bank::supply_handler))
+        .route("/cosmos/bank/v1beta1/supply/nam", get(bank::supply_denom_handler))
+        .route("/cosmos/distribution/v1beta1/community_pool", get(distribution::community_pool_handler))
+        .route("/cosmos/gov/v1beta1/proposals", get(gov::all_proposals_handler))
+        .route("/cosmos/gov/v1beta1/proposals/:id", get(gov::single_proposal_handler))
+        .route("/cosmos/gov/v1beta1/proposals/:id/tally", get(gov::proposal_tally_handler))
+        .route("/cosmos/gov/v1/proposals", get(gov_v1::all_proposals_handler))
+        .route("/cosmos/gov/v1/proposals/:id", get(gov_v1::single_proposal_handler))
+        .route("/cosmos/gov/v1/proposals/:id/tally", get(gov_v1::proposal_tally_handler))
+        .route("/cosmos/staking/v1beta1/pool", get(staking::pool_handler))
+        .route("/cosmos/staking/v1beta1/validators", get(staking::validators_handler));
+    // service endpoints that don't query chain state at a height, so a height param is simply ignored
+    let service_router = Router::new()
+        .route("/cosmos/tx/v1beta1/simulate", post(simulate::simulate_handler))
+        .route("/health", get(status::health_handler))
+        .route("/metrics", get(status::metrics_handler))
+        .route("/namada/cache/stats", get(status::cache_stats_handler))
+        .route("/namada/snapshot", get(status::snapshot_status_handler))
+        .route("/ready", get(status::ready_handler));
+    let app: Router = router
+        .route_layer(middleware::from_fn(shared::latest_state_only))
+        .merge(historical_router)
+        .merge(service_router)
+        .fallback(fallback::fallback_handler)
+        .layer(middleware::from_fn(api_error::cosmos_rejections))
+        .layer(middleware::from_fn_with_state(app_state.clone(), metrics::track_metrics))
         .with_state(app_state)
         .layer(cors);
 
-    println!("Starting server...");
-    axum::serve(listener, app).await.unwrap();
+    info!("listening on {}, using rpc {}", listen_address, config.rpc.urls.join(", "));
+    axum::serve(listener, app).await
+        .map_err(|err| {
+            error!("server error: {}", err);
+            ConfigError::Startup(format!("server error: {}", err))
+        })
 }
@@ -0,0 +1,50 @@
+use serde::Serialize;
+use namada_sdk::types::address::Address;
+
+use crate::model::staking::ConsensusKeyInfo;
+
+
+#[derive(Serialize)]
+pub struct AccountResponse {
+  pub account: BaseAccount,
+}
+
+#[derive(Serialize)]
+pub struct BaseAccount {
+  #[serde(rename = "@type")]
+  pub at_type: String,
+  pub address: Address,
+  pub pub_key: Option<AccountPubKey>,
+  pub account_number: String,
+  pub sequence: String,
+}
+
+/// Namada accounts can have several public keys and a signature threshold, which maps onto a Cosmos multisig key
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum AccountPubKey {
+  Single(ConsensusKeyInfo),
+  Multisig(MultisigPubKey),
+}
+
+#[derive(Serialize)]
+pub struct MultisigPubKey {
+  #[serde(rename = "@type")]
+  pub at_type: String,
+  pub threshold: u32,
+  pub public_keys: Vec<ConsensusKeyInfo>,
+}
+
+#[derive(Serialize)]
+pub struct AuthParamsResponse {
+  pub params: AuthParamsInfo,
+}
+
+#[derive(Serialize)]
+pub struct AuthParamsInfo {
+  pub max_memo_characters: String,
+  pub tx_sig_limit: String,
+  pub tx_size_cost_per_byte: String,
+  pub sig_verify_cost_ed25519: String,
+  pub sig_verify_cost_secp256k1: String,
+}
@@ -11,4 +11,10 @@ pub struct SupplyDenomResponse {
 pub struct SupplyResponse {
   pub supply: Vec<DenomAmount>,
   pub pagination: PaginationInfo,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize)]
+pub struct BalancesResponse {
+  pub balances: Vec<DenomAmount>,
+  pub pagination: PaginationInfo,
+}
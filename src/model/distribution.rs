@@ -3,6 +3,7 @@ use namada_sdk::types::dec::Dec;
 
 use crate::model::shared::DenomAmount;
 
+/// Every amount is in whole tokens (with the token's decimals), not base units
 #[derive(Serialize)]
 pub struct CommunityPoolResponse {
   pub pool: Vec<DenomAmount>,
@@ -19,4 +20,52 @@ pub struct DistibutionParamsInfo {
   pub base_proposer_reward: Dec,
   pub bonus_proposer_reward: Dec,
   pub withdraw_addr_enabled: bool,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize)]
+pub struct DelegatorRewardsResponse {
+  pub rewards: Vec<DelegatorValidatorReward>,
+  pub total: Vec<DenomAmount>,
+}
+
+#[derive(Serialize)]
+pub struct DelegatorValidatorReward {
+  pub validator_address: String,
+  pub reward: Vec<DenomAmount>,
+}
+
+#[derive(Serialize)]
+pub struct DelegationRewardsResponse {
+  pub rewards: Vec<DenomAmount>,
+}
+
+#[derive(Serialize)]
+pub struct ValidatorCommissionResponse {
+  pub commission: ValidatorAccumulatedCommission,
+}
+
+#[derive(Serialize)]
+pub struct ValidatorAccumulatedCommission {
+  pub commission: Vec<DenomAmount>,
+  // not part of the Cosmos response; the commission is estimated from the validator's claimable rewards
+  pub estimated: bool,
+}
+
+#[derive(Serialize)]
+pub struct ValidatorOutstandingRewardsResponse {
+  pub rewards: ValidatorOutstandingRewards,
+}
+
+#[derive(Serialize)]
+pub struct ValidatorOutstandingRewards {
+  pub rewards: Vec<DenomAmount>,
+}
+
+#[derive(Serialize)]
+pub struct ValidatorDistributionInfoResponse {
+  pub operator_address: String,
+  pub self_bond_rewards: Vec<DenomAmount>,
+  pub commission: Vec<DenomAmount>,
+  // not part of the Cosmos response; the split between self-bond rewards and commission is an estimate
+  pub estimated: bool,
+}
@@ -10,7 +10,8 @@ use std::fmt::Display;
 #[derive(Deserialize)]
 pub struct ProposalQueryParams {
   pub proposal_status: Option<u32>,
-  pub pagination: Option<PaginationQueryParams>,
+  #[serde(flatten)]
+  pub pagination: PaginationQueryParams,
 }
 
 #[derive(Serialize)]
@@ -105,6 +106,47 @@ impl From<StorageProposal> for ProposalItem {
   }
 }
 
+/// Proposal info queried from the chain, before being formatted into a versioned (v1beta1 or v1) Cosmos response
+#[derive(Clone)]
+pub struct ProposalData {
+  pub proposal: StorageProposal,
+  pub status: CosmosProposalStatus,
+  pub final_tally_result: FinalTallyInfo,
+  pub deposit: DenomAmount,
+}
+
+impl From<ProposalData> for ProposalItem {
+  fn from(value: ProposalData) -> Self {
+    let proposal = value.proposal;
+
+    // TODO: how do we find this value?
+    let submit_time = DEFAULT_TIMESTAMP.to_string();
+    // TODO: properly convert these from epochs to timestamps
+    let deposit_end_time = proposal.voting_start_epoch.to_string(); // same as voting_start_time?
+    let voting_start_time = proposal.voting_start_epoch.to_string();
+    let voting_end_time = proposal.voting_end_epoch.to_string();
+
+    ProposalItem {
+      proposal_id: proposal.id.to_string(),
+      content: ProposalInfo {
+        at_type: proposal.r#type.to_string(),
+        title: proposal.content.get("title").unwrap_or(&"".to_string()).clone(),
+        // TODO: concatenate 'abstract', 'motivation', and 'details'?
+        description: proposal.content.get("details").unwrap_or(&"".to_string()).clone(),
+        recipient: None,
+        amount: None,
+      },
+      status: value.status,
+      final_tally_result: value.final_tally_result,
+      submit_time,
+      deposit_end_time,
+      total_deposit: vec![value.deposit],
+      voting_start_time,
+      voting_end_time,
+    }
+  }
+}
+
 // TODO: in Cosmos there are different proposal types (text, community spend, parameter change, etc)
 // included fields differ between each
 // furthermore, the types don't correspond easily to Namada proposal types/info
@@ -118,7 +160,7 @@ pub struct ProposalInfo {
   pub amount: Option<Vec<DenomAmount>>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct FinalTallyInfo {
   pub yes: String,
   pub abstain: String, // does not exist in Namada
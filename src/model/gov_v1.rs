@@ -0,0 +1,141 @@
+use serde::{Serialize, Deserialize};
+use namada_sdk::{governance::storage::proposal::ProposalType, types::dec::Dec};
+
+use crate::model::{
+  gov::{CosmosProposalStatus, DepositParams, FinalTallyInfo, ProposalData, TallyParams, VotingParams},
+  shared::{DenomAmount, SuffixedDur, PaginationQueryParams, PaginationInfo, DEFAULT_TIMESTAMP},
+};
+
+
+#[derive(Deserialize)]
+pub struct ProposalV1QueryParams {
+  pub proposal_status: Option<u32>,
+  #[serde(flatten)]
+  pub pagination: PaginationQueryParams,
+}
+
+/// The v1 params response keeps the deprecated per-type objects, but the values now live in the flattened `params` object
+#[derive(Serialize)]
+pub struct ParamsGovV1Response {
+  pub voting_params: Option<VotingParams>,
+  pub deposit_params: Option<DepositParams>,
+  pub tally_params: Option<TallyParams>,
+  pub params: GovV1Params,
+}
+
+#[derive(Serialize)]
+pub struct GovV1Params {
+  pub min_deposit: Vec<DenomAmount>,
+  pub max_deposit_period: String,
+  pub voting_period: SuffixedDur,
+  pub quorum: Dec,
+  pub threshold: Dec,
+  pub veto_threshold: Dec,
+  pub min_initial_deposit_ratio: Dec,
+  pub burn_vote_quorum: bool,
+  pub burn_proposal_deposit_prevote: bool,
+  pub burn_vote_veto: bool,
+}
+
+#[derive(Serialize)]
+pub struct ProposalsV1Response {
+  pub proposals: Vec<ProposalV1Item>,
+  pub pagination: Option<PaginationInfo>,
+}
+
+#[derive(Serialize)]
+pub struct IndividualProposalV1Response {
+  pub proposal: Option<ProposalV1Item>,
+}
+
+#[derive(Serialize)]
+pub struct ProposalV1Item {
+  pub id: String,
+  pub messages: Vec<ProposalMessage>,
+  pub status: CosmosProposalStatus,
+  pub final_tally_result: TallyV1Info,
+  pub submit_time: String, //time
+  pub deposit_end_time: String, //time
+  pub total_deposit: Vec<DenomAmount>,
+  pub voting_start_time: String, //time
+  pub voting_end_time: String, //time
+  pub metadata: String,
+  pub title: String,
+  pub summary: String,
+  pub proposer: String, //address
+}
+
+impl From<ProposalData> for ProposalV1Item {
+  fn from(value: ProposalData) -> Self {
+    let proposal = value.proposal;
+    let content_field = |key: &str| proposal.content.get(key).cloned().unwrap_or_default();
+
+    // Namada proposals don't carry Cosmos messages; the closest equivalent is the proposal type
+    // (default proposal with optional wasm code, pgf steward or pgf funding), named like a Cosmos type url
+    let kind = match proposal.r#type {
+      ProposalType::Default(_) => "Default",
+      ProposalType::PGFSteward(_) => "PgfSteward",
+      ProposalType::PGFPayment(_) => "PgfFunding",
+    };
+    let messages = vec![ProposalMessage {
+      at_type: format!("/namada.governance.v1.{}Proposal", kind),
+    }];
+
+    // the Cosmos summary is a short description, which corresponds to the 'abstract' field of a Namada proposal
+    let summary = match proposal.content.get("abstract") {
+      Some(summary) => summary.clone(),
+      None => content_field("details"),
+    };
+
+    // v1 metadata is an opaque string (usually an ipfs link); we pass along the full proposal content as json
+    let metadata = serde_json::to_string(&proposal.content).unwrap_or_default();
+
+    ProposalV1Item {
+      id: proposal.id.to_string(),
+      messages,
+      status: value.status,
+      final_tally_result: TallyV1Info::from(value.final_tally_result),
+      // TODO: how do we find this value?
+      submit_time: DEFAULT_TIMESTAMP.to_string(),
+      // filled in from the start times of the voting epochs, which need to be queried
+      deposit_end_time: DEFAULT_TIMESTAMP.to_string(),
+      total_deposit: vec![value.deposit],
+      voting_start_time: DEFAULT_TIMESTAMP.to_string(),
+      voting_end_time: DEFAULT_TIMESTAMP.to_string(),
+      metadata,
+      title: content_field("title"),
+      summary,
+      proposer: proposal.author.to_string(),
+    }
+  }
+}
+
+#[derive(Serialize)]
+pub struct ProposalMessage {
+  #[serde(rename = "@type")]
+  pub at_type: String,
+}
+
+#[derive(Serialize)]
+pub struct TallyV1Info {
+  pub yes_count: String,
+  pub abstain_count: String, // does not exist in Namada
+  pub no_count: String,
+  pub no_with_veto_count: String, // does not exist in Namada
+}
+
+impl From<FinalTallyInfo> for TallyV1Info {
+  fn from(value: FinalTallyInfo) -> Self {
+    TallyV1Info {
+      yes_count: value.yes,
+      abstain_count: value.abstain,
+      no_count: value.no,
+      no_with_veto_count: value.no_with_veto,
+    }
+  }
+}
+
+#[derive(Serialize)]
+pub struct TallyV1Response {
+  pub tally: TallyV1Info,
+}
@@ -0,0 +1,23 @@
+use serde::Serialize;
+
+use crate::app::rpc_pool::RpcNodeStatus;
+
+
+#[derive(Serialize)]
+pub struct HealthResponse {
+  pub status: String, // ok/unavailable for /health, ready/not_ready for /ready
+  // why the check failed; empty when it passed
+  pub reasons: Vec<String>,
+  pub node: Option<NodeSyncInfo>,
+  pub epoch: Option<String>,
+  pub rpc_nodes: Vec<RpcNodeStatus>,
+}
+
+#[derive(Serialize)]
+pub struct NodeSyncInfo {
+  pub network: String,
+  pub catching_up: bool,
+  pub latest_block_height: String,
+  pub latest_block_time: String,
+  pub block_age_seconds: u64,
+}
@@ -0,0 +1,437 @@
+use serde::{Deserialize, Serialize};
+use base64::{encode_config, STANDARD};
+
+use crate::model::shared::{PaginationInfo, PaginationQueryParams};
+
+
+// Protobuf encoded IBC states, as stored by Namada's IBC module. Only the fields that are returned by the
+// Cosmos REST endpoints are decoded; prost skips the rest
+pub mod proto {
+  #[derive(Clone, PartialEq, prost::Message)]
+  pub struct Channel {
+    #[prost(int32, tag = "1")]
+    pub state: i32,
+    #[prost(int32, tag = "2")]
+    pub ordering: i32,
+    #[prost(message, optional, tag = "3")]
+    pub counterparty: Option<ChannelCounterparty>,
+    #[prost(string, repeated, tag = "4")]
+    pub connection_hops: Vec<String>,
+    #[prost(string, tag = "5")]
+    pub version: String,
+  }
+
+  #[derive(Clone, PartialEq, prost::Message)]
+  pub struct ChannelCounterparty {
+    #[prost(string, tag = "1")]
+    pub port_id: String,
+    #[prost(string, tag = "2")]
+    pub channel_id: String,
+  }
+
+  #[derive(Clone, PartialEq, prost::Message)]
+  pub struct ConnectionEnd {
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+    #[prost(message, repeated, tag = "2")]
+    pub versions: Vec<Version>,
+    #[prost(int32, tag = "3")]
+    pub state: i32,
+    #[prost(message, optional, tag = "4")]
+    pub counterparty: Option<ConnectionCounterparty>,
+    #[prost(uint64, tag = "5")]
+    pub delay_period: u64,
+  }
+
+  #[derive(Clone, PartialEq, prost::Message)]
+  pub struct Version {
+    #[prost(string, tag = "1")]
+    pub identifier: String,
+    #[prost(string, repeated, tag = "2")]
+    pub features: Vec<String>,
+  }
+
+  #[derive(Clone, PartialEq, prost::Message)]
+  pub struct ConnectionCounterparty {
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+    #[prost(string, tag = "2")]
+    pub connection_id: String,
+    #[prost(message, optional, tag = "3")]
+    pub prefix: Option<MerklePrefix>,
+  }
+
+  #[derive(Clone, PartialEq, prost::Message)]
+  pub struct MerklePrefix {
+    #[prost(bytes = "vec", tag = "1")]
+    pub key_prefix: Vec<u8>,
+  }
+
+  /// ibc.lightclients.tendermint.v1.ClientState
+  #[derive(Clone, PartialEq, prost::Message)]
+  pub struct TendermintClientState {
+    #[prost(string, tag = "1")]
+    pub chain_id: String,
+    #[prost(message, optional, tag = "2")]
+    pub trust_level: Option<Fraction>,
+    #[prost(message, optional, tag = "3")]
+    pub trusting_period: Option<prost_types::Duration>,
+    #[prost(message, optional, tag = "4")]
+    pub unbonding_period: Option<prost_types::Duration>,
+    #[prost(message, optional, tag = "5")]
+    pub max_clock_drift: Option<prost_types::Duration>,
+    #[prost(message, optional, tag = "6")]
+    pub frozen_height: Option<Height>,
+    #[prost(message, optional, tag = "7")]
+    pub latest_height: Option<Height>,
+    #[prost(string, repeated, tag = "9")]
+    pub upgrade_path: Vec<String>,
+  }
+
+  #[derive(Clone, PartialEq, prost::Message)]
+  pub struct Fraction {
+    #[prost(uint64, tag = "1")]
+    pub numerator: u64,
+    #[prost(uint64, tag = "2")]
+    pub denominator: u64,
+  }
+
+  #[derive(Clone, PartialEq, prost::Message)]
+  pub struct Height {
+    #[prost(uint64, tag = "1")]
+    pub revision_number: u64,
+    #[prost(uint64, tag = "2")]
+    pub revision_height: u64,
+  }
+}
+
+pub const TENDERMINT_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.tendermint.v1.ClientState";
+
+#[derive(Deserialize)]
+pub struct IbcQueryParams {
+  #[serde(flatten)]
+  pub pagination: PaginationQueryParams,
+}
+
+#[derive(Serialize)]
+pub struct HeightInfo {
+  pub revision_number: String,
+  pub revision_height: String,
+}
+
+impl From<proto::Height> for HeightInfo {
+  fn from(value: proto::Height) -> Self {
+    HeightInfo {
+      revision_number: value.revision_number.to_string(),
+      revision_height: value.revision_height.to_string(),
+    }
+  }
+}
+
+impl HeightInfo {
+  /// Namada's chain id doesn't follow the `{chain}-{revision}` format, so its revision number is always 0
+  pub fn namada_height(height: u64) -> Self {
+    HeightInfo {
+      revision_number: "0".to_string(),
+      revision_height: height.to_string(),
+    }
+  }
+}
+
+#[derive(Serialize)]
+pub struct ChannelsResponse {
+  pub channels: Vec<IdentifiedChannel>,
+  pub pagination: PaginationInfo,
+  pub height: HeightInfo,
+}
+
+#[derive(Serialize)]
+pub struct IdentifiedChannel {
+  pub state: String,
+  pub ordering: String,
+  pub counterparty: ChannelCounterpartyInfo,
+  pub connection_hops: Vec<String>,
+  pub version: String,
+  pub port_id: String,
+  pub channel_id: String,
+}
+
+impl IdentifiedChannel {
+  pub fn new(port_id: String, channel_id: String, channel: proto::Channel) -> Self {
+    let counterparty = channel.counterparty.unwrap_or_default();
+    IdentifiedChannel {
+      state: channel_state_name(channel.state).to_string(),
+      ordering: channel_order_name(channel.ordering).to_string(),
+      counterparty: ChannelCounterpartyInfo {
+        port_id: counterparty.port_id,
+        channel_id: counterparty.channel_id,
+      },
+      connection_hops: channel.connection_hops,
+      version: channel.version,
+      port_id,
+      channel_id,
+    }
+  }
+}
+
+#[derive(Serialize)]
+pub struct ChannelCounterpartyInfo {
+  pub port_id: String,
+  pub channel_id: String,
+}
+
+#[derive(Serialize)]
+pub struct ConnectionsResponse {
+  pub connections: Vec<IdentifiedConnection>,
+  pub pagination: PaginationInfo,
+  pub height: HeightInfo,
+}
+
+#[derive(Serialize)]
+pub struct IdentifiedConnection {
+  pub id: String,
+  pub client_id: String,
+  pub versions: Vec<VersionInfo>,
+  pub state: String,
+  pub counterparty: ConnectionCounterpartyInfo,
+  pub delay_period: String,
+}
+
+impl IdentifiedConnection {
+  pub fn new(id: String, connection: proto::ConnectionEnd) -> Self {
+    let counterparty = connection.counterparty.unwrap_or_default();
+    IdentifiedConnection {
+      id,
+      client_id: connection.client_id,
+      versions: connection.versions.into_iter()
+        .map(|version| VersionInfo { identifier: version.identifier, features: version.features })
+        .collect(),
+      state: connection_state_name(connection.state).to_string(),
+      counterparty: ConnectionCounterpartyInfo {
+        client_id: counterparty.client_id,
+        connection_id: counterparty.connection_id,
+        prefix: MerklePrefixInfo {
+          key_prefix: encode_config(counterparty.prefix.unwrap_or_default().key_prefix, STANDARD),
+        },
+      },
+      delay_period: connection.delay_period.to_string(),
+    }
+  }
+}
+
+#[derive(Serialize)]
+pub struct VersionInfo {
+  pub identifier: String,
+  pub features: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct ConnectionCounterpartyInfo {
+  pub client_id: String,
+  pub connection_id: String,
+  pub prefix: MerklePrefixInfo,
+}
+
+#[derive(Serialize)]
+pub struct MerklePrefixInfo {
+  pub key_prefix: String,
+}
+
+#[derive(Serialize)]
+pub struct ClientStatesResponse {
+  pub client_states: Vec<IdentifiedClientState>,
+  pub pagination: PaginationInfo,
+}
+
+#[derive(Serialize)]
+pub struct IdentifiedClientState {
+  pub client_id: String,
+  pub client_state: ClientStateInfo,
+}
+
+/// Only Tendermint light clients are decoded; other client types are returned with just their type url
+#[derive(Serialize)]
+pub struct ClientStateInfo {
+  #[serde(rename = "@type")]
+  pub at_type: String,
+  #[serde(flatten)]
+  pub tendermint: Option<TendermintClientStateInfo>,
+}
+
+#[derive(Serialize)]
+pub struct TendermintClientStateInfo {
+  pub chain_id: String,
+  pub trust_level: FractionInfo,
+  pub trusting_period: String,
+  pub unbonding_period: String,
+  pub max_clock_drift: String,
+  pub frozen_height: HeightInfo,
+  pub latest_height: HeightInfo,
+  pub upgrade_path: Vec<String>,
+}
+
+impl From<proto::TendermintClientState> for TendermintClientStateInfo {
+  fn from(value: proto::TendermintClientState) -> Self {
+    let trust_level = value.trust_level.unwrap_or_default();
+    TendermintClientStateInfo {
+      chain_id: value.chain_id,
+      trust_level: FractionInfo {
+        numerator: trust_level.numerator.to_string(),
+        denominator: trust_level.denominator.to_string(),
+      },
+      trusting_period: format_duration(value.trusting_period),
+      unbonding_period: format_duration(value.unbonding_period),
+      max_clock_drift: format_duration(value.max_clock_drift),
+      frozen_height: HeightInfo::from(value.frozen_height.unwrap_or_default()),
+      latest_height: HeightInfo::from(value.latest_height.unwrap_or_default()),
+      upgrade_path: value.upgrade_path,
+    }
+  }
+}
+
+#[derive(Serialize)]
+pub struct FractionInfo {
+  pub numerator: String,
+  pub denominator: String,
+}
+
+/// A channel along with the connection and light client it's built on
+#[derive(Serialize)]
+pub struct ChannelResponse {
+  pub channel: IdentifiedChannel,
+  pub connection: Option<IdentifiedConnection>,
+  pub client_state: Option<IdentifiedClientState>,
+  pub proof: Option<String>,
+  pub proof_height: HeightInfo,
+}
+
+#[derive(Serialize)]
+pub struct PacketCommitmentsResponse {
+  pub commitments: Vec<PacketState>,
+  pub pagination: PaginationInfo,
+  pub height: HeightInfo,
+}
+
+#[derive(Serialize)]
+pub struct PacketAcknowledgementsResponse {
+  pub acknowledgements: Vec<PacketState>,
+  pub pagination: PaginationInfo,
+  pub height: HeightInfo,
+}
+
+#[derive(Serialize)]
+pub struct PacketState {
+  pub port_id: String,
+  pub channel_id: String,
+  pub sequence: String,
+  pub data: String, // base64 commitment/acknowledgement hash
+}
+
+#[derive(Serialize)]
+pub struct UnreceivedSequencesResponse {
+  pub sequences: Vec<String>,
+  pub height: HeightInfo,
+}
+
+#[derive(Serialize)]
+pub struct DenomTracesResponse {
+  pub denom_traces: Vec<DenomTrace>,
+  pub pagination: PaginationInfo,
+}
+
+#[derive(Serialize)]
+pub struct DenomTraceResponse {
+  pub denom_trace: DenomTrace,
+}
+
+#[derive(Serialize)]
+pub struct DenomTrace {
+  pub path: String,
+  pub base_denom: String,
+}
+
+impl DenomTrace {
+  /// Splits a full denom (eg: 'transfer/channel-0/uosmo') into its trace path and base denom
+  /// The path is made of {port}/{channel} pairs; whatever follows is the base denom, which may itself contain slashes
+  pub fn from_full_denom(denom: &str) -> Self {
+    let segments: Vec<&str> = denom.split('/').collect();
+    let mut path_len = 0;
+    while path_len + 2 < segments.len() && segments[path_len + 1].starts_with("channel-") {
+      path_len += 2;
+    }
+
+    DenomTrace {
+      path: segments[..path_len].join("/"),
+      base_denom: segments[path_len..].join("/"),
+    }
+  }
+}
+
+fn format_duration(duration: Option<prost_types::Duration>) -> String {
+  format!("{}s", duration.map(|duration| duration.seconds).unwrap_or(0))
+}
+
+fn channel_state_name(state: i32) -> &'static str {
+  match state {
+    1 => "STATE_INIT",
+    2 => "STATE_TRYOPEN",
+    3 => "STATE_OPEN",
+    4 => "STATE_CLOSED",
+    _ => "STATE_UNINITIALIZED_UNSPECIFIED",
+  }
+}
+
+fn channel_order_name(ordering: i32) -> &'static str {
+  match ordering {
+    1 => "ORDER_UNORDERED",
+    2 => "ORDER_ORDERED",
+    _ => "ORDER_NONE_UNSPECIFIED",
+  }
+}
+
+fn connection_state_name(state: i32) -> &'static str {
+  match state {
+    1 => "STATE_INIT",
+    2 => "STATE_TRYOPEN",
+    3 => "STATE_OPEN",
+    _ => "STATE_UNINITIALIZED_UNSPECIFIED",
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn trace(denom: &str) -> (String, String) {
+    let trace = DenomTrace::from_full_denom(denom);
+    (trace.path, trace.base_denom)
+  }
+
+  #[test]
+  fn native_denom_has_no_path() {
+    assert_eq!(trace("uosmo"), ("".to_string(), "uosmo".to_string()));
+  }
+
+  #[test]
+  fn single_hop() {
+    assert_eq!(trace("transfer/channel-0/uosmo"), ("transfer/channel-0".to_string(), "uosmo".to_string()));
+  }
+
+  #[test]
+  fn multi_hop() {
+    assert_eq!(
+      trace("transfer/channel-0/transfer/channel-12/uatom"),
+      ("transfer/channel-0/transfer/channel-12".to_string(), "uatom".to_string()),
+    );
+  }
+
+  #[test]
+  fn base_denom_with_slashes() {
+    assert_eq!(trace("transfer/channel-0/gamm/pool/1"), ("transfer/channel-0".to_string(), "gamm/pool/1".to_string()));
+  }
+
+  #[test]
+  fn non_channel_segment_is_part_of_base_denom() {
+    assert_eq!(trace("transfer/foo/uosmo"), ("".to_string(), "transfer/foo/uosmo".to_string()));
+  }
+}
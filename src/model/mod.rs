@@ -1,9 +1,17 @@
+pub mod auth;
 pub mod bank;
 pub mod distribution;
 pub mod gov;
+pub mod gov_v1;
+pub mod health;
 pub mod ibc;
 pub mod mint;
+pub mod pgf;
 pub mod shared;
+pub mod simulate;
 pub mod slashing;
 pub mod staking;
 pub mod tendermint;
+#[cfg(feature = "indexer")]
+pub mod tx;
+pub mod uptime;
@@ -0,0 +1,37 @@
+use serde::Serialize;
+use namada_sdk::types::{address::Address, dec::Dec};
+
+use crate::model::shared::DenomAmount;
+
+
+#[derive(Serialize)]
+pub struct StewardsResponse {
+  pub stewards: Vec<StewardInfo>,
+  pub stewards_inflation_rate: Dec,
+}
+
+#[derive(Serialize)]
+pub struct StewardInfo {
+  pub address: Address,
+  pub reward_distribution: Vec<RewardShare>,
+}
+
+#[derive(Serialize)]
+pub struct RewardShare {
+  pub address: Address,
+  pub share: Dec,
+}
+
+#[derive(Serialize)]
+pub struct FundingsResponse {
+  pub fundings: Vec<FundingInfo>,
+  pub pgf_inflation_rate: Dec,
+}
+
+/// A continuous funding stream, paid out of the PGF inflation every epoch
+#[derive(Serialize)]
+pub struct FundingInfo {
+  pub id: String,
+  pub target: String, // Namada address, or the receiver address on the counterparty chain for ibc targets
+  pub amount: DenomAmount,
+}
@@ -1,6 +1,7 @@
-use serde::{Serialize, Serializer, Deserialize};
+use serde::{de, Serialize, Serializer, Deserialize, Deserializer};
 use namada_sdk::types::token::Amount;
-use std::time::Duration;
+use std::{fmt::Display, str::FromStr, time::Duration};
+use base64::{decode, encode};
 use tendermint_proto::google::protobuf::Timestamp;
 
 pub const NAM: &str = "nam";
@@ -10,15 +11,81 @@ pub const ZERO_TIMESTAMP: Timestamp = Timestamp {
   nanos: 0,
 };
 
-#[derive(Deserialize)]
+pub const DEFAULT_PAGE_LIMIT: usize = 100;
+
+/// Cosmos pagination query params, eg: `?pagination.limit=20&pagination.reverse=true`
+/// Meant to be `#[serde(flatten)]`ed into a handler's query params; flattened fields are buffered as strings,
+/// so the values are parsed by hand
+#[derive(Deserialize, Default)]
 pub struct PaginationQueryParams {
+  #[serde(rename = "pagination.key", default)]
+  pub key: Option<String>,
+  #[serde(rename = "pagination.limit", default, deserialize_with = "from_str_opt")]
   pub limit: Option<u32>,
+  #[serde(rename = "pagination.offset", default, deserialize_with = "from_str_opt")]
   pub offset: Option<u32>,
+  #[serde(rename = "pagination.count_total", default, deserialize_with = "from_str_opt")]
   pub count_total: Option<bool>,
+  #[serde(rename = "pagination.reverse", default, deserialize_with = "from_str_opt")]
   pub reverse: Option<bool>,
 }
 
-#[derive(Serialize)]
+impl PaginationQueryParams {
+  /// Returns the requested page of items, along with the pagination info for the response
+  /// `pagination.key` takes precedence over `pagination.offset`; the keys we hand out just encode the next offset
+  pub fn paginate<T>(&self, mut items: Vec<T>) -> (Vec<T>, PaginationInfo) {
+    let total = items.len();
+    if self.reverse.unwrap_or(false) {
+      items.reverse();
+    }
+
+    let (offset, limit) = self.offset_and_limit();
+    let offset = offset.min(total);
+
+    let page: Vec<T> = items.into_iter().skip(offset).take(limit).collect();
+    let info = Self::page_info(offset, page.len(), total);
+
+    (page, info)
+  }
+
+  /// The requested (offset, limit), for when the paging is done by the data source rather than `paginate`
+  pub fn offset_and_limit(&self) -> (usize, usize) {
+    let key_offset = self.key.as_ref()
+      .filter(|key| !key.is_empty())
+      .and_then(|key| decode(key).ok())
+      .and_then(|bytes| String::from_utf8(bytes).ok())
+      .and_then(|offset| offset.parse::<usize>().ok());
+    let offset = key_offset.unwrap_or(self.offset.unwrap_or(0) as usize);
+    let limit = match self.limit {
+      Some(limit) if limit > 0 => limit as usize,
+      _ => DEFAULT_PAGE_LIMIT,
+    };
+
+    (offset, limit)
+  }
+
+  /// Pagination info for a page of `page_len` items starting at `offset`, out of `total`
+  pub fn page_info(offset: usize, page_len: usize, total: usize) -> PaginationInfo {
+    let next_offset = offset + page_len;
+    let next_key = if next_offset < total { Some(encode(next_offset.to_string())) } else { None };
+
+    PaginationInfo { next_key, total: Some(total.to_string()) }
+  }
+}
+
+fn from_str_opt<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+  where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display {
+
+  match Option::<String>::deserialize(deserializer)? {
+    Some(value) if !value.is_empty() => value.parse::<T>().map(Some).map_err(de::Error::custom),
+    _ => Ok(None),
+  }
+}
+
+#[derive(Serialize, Clone)]
 pub struct PaginationInfo {
   pub next_key: Option<String>,
   pub total: Option<String>,
@@ -48,6 +115,22 @@ impl DenomAmount {
   }
 }
 
+/// A Cosmos abci event, eg: in a tx response
+#[derive(Serialize)]
+pub struct EventInfo {
+  #[serde(rename = "type")]
+  pub kind: String,
+  pub attributes: Vec<EventAttribute>,
+}
+
+#[derive(Serialize)]
+pub struct EventAttribute {
+  pub key: String,
+  pub value: String,
+  pub index: bool,
+}
+
+#[derive(Clone)]
 pub struct SuffixedDur(pub Duration);
 
 impl Serialize for SuffixedDur {
@@ -60,4 +143,70 @@ impl Serialize for SuffixedDur {
 
       serializer.serialize_str(&formatted_duration)
   }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn params(key: Option<&str>, offset: Option<u32>, limit: Option<u32>, reverse: Option<bool>) -> PaginationQueryParams {
+    PaginationQueryParams {
+      key: key.map(str::to_string),
+      limit,
+      offset,
+      count_total: None,
+      reverse,
+    }
+  }
+
+  #[test]
+  fn key_takes_precedence_over_offset() {
+    let key = encode("5");
+    assert_eq!(params(Some(&key), Some(2), None, None).offset_and_limit(), (5, DEFAULT_PAGE_LIMIT));
+  }
+
+  #[test]
+  fn invalid_or_empty_key_falls_back_to_offset() {
+    assert_eq!(params(Some("not base64!"), Some(2), None, None).offset_and_limit().0, 2);
+    assert_eq!(params(Some(""), Some(3), None, None).offset_and_limit().0, 3);
+    assert_eq!(params(None, None, None, None).offset_and_limit().0, 0);
+  }
+
+  #[test]
+  fn zero_limit_uses_default() {
+    assert_eq!(params(None, None, Some(0), None).offset_and_limit().1, DEFAULT_PAGE_LIMIT);
+    assert_eq!(params(None, None, Some(7), None).offset_and_limit().1, 7);
+  }
+
+  #[test]
+  fn paginate_returns_page_and_next_key() {
+    let (page, info) = params(None, Some(2), Some(3), None).paginate((0..10).collect());
+    assert_eq!(page, vec![2, 3, 4]);
+    assert_eq!(info.next_key, Some(encode("5")));
+    assert_eq!(info.total, Some("10".to_string()));
+  }
+
+  #[test]
+  fn paginate_follows_next_key() {
+    let (_, info) = params(None, None, Some(4), None).paginate((0..10).collect::<Vec<u32>>());
+    let (page, info) = params(info.next_key.as_deref(), None, Some(4), None).paginate((0..10).collect());
+    assert_eq!(page, vec![4, 5, 6, 7]);
+    let (page, info) = params(info.next_key.as_deref(), None, Some(4), None).paginate((0..10).collect());
+    assert_eq!(page, vec![8, 9]);
+    assert_eq!(info.next_key, None);
+  }
+
+  #[test]
+  fn paginate_reverse() {
+    let (page, info) = params(None, None, Some(3), Some(true)).paginate((0..5).collect());
+    assert_eq!(page, vec![4, 3, 2]);
+    assert_eq!(info.next_key, Some(encode("3")));
+  }
+
+  #[test]
+  fn paginate_offset_past_end() {
+    let (page, info) = params(None, Some(20), None, None).paginate((0..5).collect::<Vec<u32>>());
+    assert!(page.is_empty());
+    assert_eq!(info.next_key, None);
+    assert_eq!(info.total, Some("5".to_string()));
+  }
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::model::shared::EventInfo;
+
+
+#[derive(Deserialize)]
+pub struct SimulateRequest {
+  // the borsh serialized Namada tx, base64 encoded
+  pub tx_bytes: Option<String>,
+  // the deprecated Cosmos `tx` json can't describe a Namada tx, so it isn't supported
+  pub tx: Option<Value>,
+}
+
+#[derive(Serialize)]
+pub struct SimulateResponse {
+  pub gas_info: GasInfo,
+  pub result: SimulateResult,
+}
+
+#[derive(Serialize)]
+pub struct GasInfo {
+  // the wrapper's gas limit
+  pub gas_wanted: String,
+  pub gas_used: String,
+}
+
+#[derive(Serialize)]
+pub struct SimulateResult {
+  pub data: String,
+  pub log: String,
+  pub events: Vec<EventInfo>,
+  pub msg_responses: Vec<Value>,
+}
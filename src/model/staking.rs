@@ -1,26 +1,29 @@
 use serde::{Deserialize, Serialize};
-use namada_sdk::types::{address::Address, dec::Dec, token::Amount};
+use namada_sdk::types::{address::Address, dec::Dec, key::common, token::Amount};
+use base64::{encode_config, STANDARD};
+use namada_sdk::proof_of_stake::Epoch;
 use crate::model::shared::{SuffixedDur, PaginationInfo, PaginationQueryParams};
 
 
 #[derive(Deserialize)]
 pub struct ValidatorsQueryParams {
   pub status: Option<CosmosValStatus>,
-  pub pagination: Option<PaginationQueryParams>,
+  #[serde(flatten)]
+  pub pagination: PaginationQueryParams,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct PoolResponse {
   pub pool: PoolInfo,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct PoolInfo {
   pub not_bonded_tokens: String,
   pub bonded_tokens: Amount,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct ParamsResponse {
   pub unbonding_time: SuffixedDur,
   pub max_validators: u32,
@@ -44,9 +47,9 @@ impl ValidatorsResponse {
   }
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct ValidatorInfo {
-  pub operator_address: Address,
+  pub operator_address: String, // native, or valoper address in compatibility mode
   pub consensus_pubkey: ConsensusKeyInfo,
   pub jailed: bool,
   pub status: CosmosValStatus,
@@ -59,14 +62,30 @@ pub struct ValidatorInfo {
   pub min_self_delegation: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct ConsensusKeyInfo {
   #[serde(rename = "@type")]
   pub at_type: String,
   pub key: String,
 }
 
-#[derive(Serialize)]
+/// Formats a Namada public key as a Cosmos `Any` encoded public key, ie: the key type and the base64 of the raw key bytes
+impl From<&common::PublicKey> for ConsensusKeyInfo {
+  fn from(value: &common::PublicKey) -> Self {
+    let at_type = match value {
+      common::PublicKey::Ed25519(_) => "/cosmos.crypto.ed25519.PubKey",
+      common::PublicKey::Secp256k1(_) => "/cosmos.crypto.secp256k1.PubKey",
+    };
+    // the first byte of the borsh encoding is the key type
+    let key_bytes = borsh::to_vec(value).unwrap_or_default();
+    ConsensusKeyInfo {
+      at_type: at_type.to_string(),
+      key: encode_config(key_bytes.get(1..).unwrap_or_default(), STANDARD),
+    }
+  }
+}
+
+#[derive(Serialize, Clone)]
 pub struct ValidatorDescription {
   pub moniker: Address,
   pub identity: Option<String>,
@@ -87,7 +106,7 @@ impl ValidatorDescription {
   }
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct CommissionInfo {
   pub commission_rates: RatesInfo,
   pub update_time: String, // time
@@ -106,9 +125,11 @@ impl Default for CommissionInfo {
   }
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct RatesInfo {
   pub rate: Dec,
+  // not derived from chain data: Namada has no per-validator cap, so the rate can be raised up to 100%
+  // (limited only by max_change_rate per epoch) and 1 is the real maximum
   pub max_rate: Dec,
   pub max_change_rate: Dec,
 }
@@ -133,3 +154,19 @@ impl PartialEq for CosmosValStatus {
     }
   }
 }
+
+#[derive(Serialize)]
+pub struct CommissionHistoryResponse {
+  pub operator_address: String,
+  pub max_change_rate: Option<Dec>,
+  pub history: Vec<CommissionChange>,
+}
+
+/// A commission rate that took effect at `epoch`; changes only take effect after the pipeline length,
+/// so the most recent entry may be for a future epoch, in which case `update_time` is unknown
+#[derive(Serialize)]
+pub struct CommissionChange {
+  pub epoch: Epoch,
+  pub rate: Dec,
+  pub update_time: Option<String>, // time
+}
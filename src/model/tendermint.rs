@@ -52,6 +52,9 @@ pub struct BlockHeader {
   pub last_results_hash: Option<String>,
   pub evidence_hash: Option<String>,
   pub proposer_address: String,
+  // the Namada address of the proposer; filled in by the handler since it needs the consensus address lookup
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub proposer_validator: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -192,6 +195,7 @@ impl From<block::Response> for BlockResponse {
         last_results_hash: Some(hash_to_base64string(value.block.header.last_results_hash.unwrap_or(Hash::None))),
         evidence_hash: Some(hash_to_base64string(value.block.header.evidence_hash.unwrap_or(Hash::None))),
         proposer_address,
+        proposer_validator: None,
       },
       data,
       evidence,
@@ -271,17 +275,25 @@ struct BuildDep {
   sum: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct ValidatorSetsResponse {
   pub block_height: String,
   pub validators: Vec<ValidatorInfo>,
   pub pagination: PaginationInfo,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct ValidatorInfo {
-  pub address: Address,
+  pub address: String, // native, or valcons address in compatibility mode
   pub pub_key: ConsensusKeyInfo,
   pub voting_power: Amount,
   pub proposer_priority: String,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize)]
+pub struct ConsensusAddressResponse {
+  pub consensus_address: String, // hex
+  pub valcons_address: String,
+  pub validator_address: Address,
+  pub operator_address: String, // native, or valoper address in compatibility mode
+}
@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::indexer::store::{StoredEvent, StoredTx};
+use crate::model::shared::{EventAttribute, EventInfo, PaginationInfo, PaginationQueryParams};
+
+
+#[derive(Deserialize)]
+pub struct TxSearchQueryParams {
+  // eg: `message.sender='tnam...'` or `tx.height=100`, multiple conditions are joined with ` AND `
+  pub events: Option<String>,
+  // the newer sdk versions send the same conditions as `query`
+  pub query: Option<String>,
+  #[serde(flatten)]
+  pub pagination: PaginationQueryParams,
+}
+
+#[derive(Deserialize)]
+pub struct AccountTxsQueryParams {
+  #[serde(flatten)]
+  pub pagination: PaginationQueryParams,
+}
+
+#[derive(Serialize)]
+pub struct GetTxResponse {
+  pub tx: TxInfo,
+  pub tx_response: TxResponse,
+}
+
+#[derive(Serialize)]
+pub struct GetTxsEventResponse {
+  pub txs: Vec<TxInfo>,
+  pub tx_responses: Vec<TxResponse>,
+  pub pagination: PaginationInfo,
+  pub total: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct TxInfo {
+  pub body: TxBody,
+  pub auth_info: AuthInfo,
+  pub signatures: Vec<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct TxBody {
+  // a Namada tx has a single 'message': its decoded data, typed by the tx's wasm code tag
+  pub messages: Vec<Value>,
+  pub memo: String,
+  pub timeout_height: String,
+  pub extension_options: Vec<Value>,
+  pub non_critical_extension_options: Vec<Value>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct AuthInfo {
+  pub signer_infos: Vec<Value>,
+  pub fee: FeeInfo,
+}
+
+#[derive(Serialize, Clone)]
+pub struct FeeInfo {
+  // Namada fees are a price per gas unit, so this is the amount per unit rather than the total
+  pub amount: Vec<FeeAmount>,
+  pub gas_limit: String,
+  pub payer: String,
+  pub granter: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct FeeAmount {
+  pub denom: String,
+  pub amount: String,
+}
+
+#[derive(Serialize)]
+pub struct TxResponse {
+  pub height: String,
+  pub txhash: String,
+  pub codespace: String,
+  pub code: u32,
+  pub data: String,
+  pub raw_log: String,
+  pub logs: Vec<Value>,
+  pub info: String,
+  pub gas_wanted: String,
+  pub gas_used: String,
+  pub tx: Value,
+  pub timestamp: String,
+  pub events: Vec<EventInfo>,
+}
+
+impl From<&StoredTx> for TxInfo {
+  fn from(tx: &StoredTx) -> Self {
+    let mut message = match serde_json::from_str::<Value>(&tx.data) {
+      Ok(Value::Object(data)) => data,
+      _ => Map::new(),
+    };
+    let kind = tx.code_tag.as_deref().map(|tag| tag.trim_end_matches(".wasm")).unwrap_or(&tx.tx_type);
+    message.insert("@type".to_string(), Value::String(format!("/namada.tx.{}", kind)));
+
+    TxInfo {
+      body: TxBody {
+        messages: vec![Value::Object(message)],
+        memo: tx.memo.clone().unwrap_or_default(),
+        timeout_height: "0".to_string(),
+        extension_options: Vec::new(),
+        non_critical_extension_options: Vec::new(),
+      },
+      auth_info: AuthInfo {
+        signer_infos: Vec::new(),
+        fee: FeeInfo {
+          amount: match (&tx.fee_token, &tx.fee_amount) {
+            (Some(denom), Some(amount)) => vec![FeeAmount { denom: denom.clone(), amount: amount.clone() }],
+            _ => Vec::new(),
+          },
+          gas_limit: tx.gas_limit.clone().unwrap_or_else(|| "0".to_string()),
+          payer: tx.fee_payer.clone().unwrap_or_default(),
+          granter: String::new(),
+        },
+      },
+      signatures: Vec::new(),
+    }
+  }
+}
+
+impl From<&StoredTx> for TxResponse {
+  fn from(tx: &StoredTx) -> Self {
+    let mut tx_json = serde_json::to_value(TxInfo::from(tx)).unwrap_or(json!({}));
+    if let Value::Object(tx_json) = &mut tx_json {
+      tx_json.insert("@type".to_string(), Value::String("/cosmos.tx.v1beta1.Tx".to_string()));
+    }
+
+    TxResponse {
+      height: tx.height.to_string(),
+      txhash: tx.hash.clone(),
+      codespace: String::new(),
+      code: tx.result_code,
+      data: String::new(),
+      raw_log: tx.result_log.clone(),
+      logs: Vec::new(),
+      info: String::new(),
+      gas_wanted: tx.gas_limit.clone().unwrap_or_else(|| "0".to_string()),
+      gas_used: tx.gas_used.clone(),
+      tx: tx_json,
+      timestamp: tx.time.clone(),
+      events: tx.events.iter().map(EventInfo::from).collect(),
+    }
+  }
+}
+
+impl From<&StoredEvent> for EventInfo {
+  fn from(event: &StoredEvent) -> Self {
+    let attributes = match serde_json::from_str::<Value>(&event.attributes) {
+      Ok(Value::Object(attributes)) => attributes.into_iter()
+        .map(|(key, value)| EventAttribute {
+          key,
+          value: value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string()),
+          index: true,
+        })
+        .collect(),
+      _ => Vec::new(),
+    };
+
+    EventInfo { kind: event.kind.clone(), attributes }
+  }
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use namada_sdk::types::address::Address;
+
+
+#[derive(Deserialize)]
+pub struct UptimeQueryParams {
+  // number of recent blocks to count over
+  pub window: Option<u64>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct UptimeResponse {
+  pub from_height: String,
+  pub to_height: String,
+  pub window: String,
+  pub validators: Vec<ValidatorUptime>,
+}
+
+#[derive(Serialize)]
+pub struct ValidatorUptimeResponse {
+  pub from_height: String,
+  pub to_height: String,
+  pub window: String,
+  pub uptime: ValidatorUptime,
+  // the heights of the blocks the validator didn't sign, for drawing an uptime grid
+  pub missed_heights: Vec<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ValidatorUptime {
+  pub operator_address: Option<String>, // native, or valoper address in compatibility mode; None if the consensus address is unknown
+  pub consensus_address: String, // hex
+  pub signed: String,
+  pub missed: String,
+  pub proposed: String,
+  // signed / (signed + missed)
+  pub uptime: f64,
+  #[serde(skip)]
+  pub validator: Option<Address>,
+}